@@ -0,0 +1,326 @@
+//! # Setup Ceremony
+//!
+//! A powers of tau ceremony for the public key, where every participant multiplies the
+//! trapdoor by a fresh secret τ_i. After n contributions α = α_0 · τ_1 · ... · τ_n, which
+//! nobody knows as long as one participant deleted their τ_i.
+//!
+//! A contribution raises every power α^i to (α·τ)^i, for g, h and g2 alike, and comes with
+//! a `ContributionProof`:
+//! * the public key g^τ with a Schnorr proof of knowledge of τ, so that τ cannot be chosen
+//!   to cancel the earlier contributions
+//! * the new g2^α', linked to the previous one by the pairing ratio e(g^τ, g2^α) = e(g, g2^α')
+//!
+//! Together with `Pk::verify`, which checks that the new key consists of successive powers of α',
+//! this shows that the new key is the old one updated by the τ of the Schnorr proof.
+//!
+//! Contributions only update α. The hiding generator h is carried over unchanged, so the
+//! ceremony removes trust in the initial key only if nobody knows log_g(h): whoever knows it
+//! can open a hiding commitment to a different set. Keys from `setup` qualify, since h is
+//! hashed to the curve.
+//!
+//! A `Transcript` records the initial key, the current key and the proofs of all contributions.
+//! Participants pass it on as a file, see `Transcript::to_bytes`, and anyone can verify the whole
+//! chain offline with `Transcript::verify`.
+//!
+//! ```
+//! use kzg::{setup, Pk};
+//! use kzg::curve::FastCurve;
+//! use kzg::ceremony::Transcript;
+//!
+//! let mut rng = rand::rng();
+//! let initial: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let mut transcript = Transcript::new(initial);
+//! for _ in 0..3 {
+//!     // every participant reads the file, contributes and writes it back
+//!     let mut received = Transcript::<FastCurve>::from_bytes(&transcript.to_bytes()).expect("transcript is valid");
+//!     received.contribute(&mut rng);
+//!     transcript = received;
+//! }
+//! assert!(transcript.verify().is_ok());
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use crate::curve::Curve;
+use crate::{KzgError, Pk};
+
+
+/// Proof that a key was updated with a secret known to the participant
+///
+/// # Fields
+///
+/// * `alpha_g2` - The updated g2^α' = g2^(α·τ)
+/// * `tau_g1` - The public key g^τ of the participant
+/// * `nonce` - The Schnorr nonce g^k
+/// * `response` - The Schnorr response k - c · τ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContributionProof<T: Curve> {
+    pub alpha_g2: T::G2,
+    pub tau_g1: T::G1,
+    pub nonce: T::G1,
+    pub response: T::Scalar
+}
+
+
+/// The initial key of a ceremony, the current key and the proofs of all contributions
+pub struct Transcript<T: Curve> {
+    pub(crate) initial: Pk<T>,
+    pub(crate) current: Pk<T>,
+    pub(crate) proofs: Vec<ContributionProof<T>>
+}
+
+
+/// Updates a key with a fresh secret τ
+///
+/// The secret is dropped when the function returns.
+///
+/// # Arguments
+///
+/// * `pk` - The current public key
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// The updated public key and the proof of the contribution
+///
+pub fn contribute<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, rng: &mut R) -> (Pk<T>, ContributionProof<T>) {
+    let tau = T::random_scalar(rng);
+
+    // the power at index i is α^(d-i), so it is multiplied by τ^(d-i)
+    let d = pk.g_powers.len() - 1;
+    let mut tau_powers = vec![T::scalar_from_literal(&1); d + 1];
+    for i in (0..d).rev() {
+        tau_powers[i] = tau_powers[i + 1] * tau;
+    }
+
+    let updated = Pk {
+        g_powers: pk.g_powers.iter().zip(&tau_powers).map(|(p, t)| T::g1mul(t, p)).collect(),
+        h_powers: pk.h_powers.iter().zip(&tau_powers).map(|(p, t)| T::g1mul(t, p)).collect(),
+        g2_powers: pk.g2_powers.iter().zip(&tau_powers).map(|(p, t)| T::g2mul(t, p)).collect(),
+        h1: pk.h1,
+        alpha_g2: T::g2mul(&tau, &pk.alpha_g2)
+    };
+
+    let tau_g1 = T::g1mul(&tau, &T::g1());
+    let k = T::random_scalar(rng);
+    let nonce = T::g1mul(&k, &T::g1());
+    let c = contribution_challenge::<T>(&pk.alpha_g2, &updated.alpha_g2, tau_g1, nonce);
+
+    let proof = ContributionProof { alpha_g2: updated.alpha_g2, tau_g1, nonce, response: k - c * tau };
+    (updated, proof)
+}
+
+
+/// Verifies that `next` is `previous` updated by the contribution of `proof`
+///
+/// # Arguments
+///
+/// * `previous` - The key before the contribution
+/// * `next` - The key after the contribution
+/// * `proof` - The proof returned by `contribute`
+///
+/// # Returns
+///
+/// `Ok(())` if the contribution is valid
+///
+/// # Errors
+///
+/// * `KzgError::MalformedProof` if the keys differ in degree or hiding generator
+/// * `KzgError::InvalidEncoding` if `next` holds the identity or a point outside the subgroup
+/// * `KzgError::SchnorrFailed` if the proof of knowledge of τ is invalid
+/// * `KzgError::PairingCheckFailed` if the keys are not related by τ or `next` is not a valid key
+///
+pub fn verify_contribution<T: Curve>(previous: &Pk<T>, next: &Pk<T>, proof: &ContributionProof<T>) -> Result<(), KzgError> {
+    if previous.g_powers.len() != next.g_powers.len() || previous.h1 != next.h1 || proof.alpha_g2 != next.alpha_g2 {
+        return Err(KzgError::MalformedProof);
+    }
+    check_step(&previous.alpha_g2, proof)?;
+    next.verify()
+}
+
+
+impl<T: Curve> Transcript<T> {
+    /// Starts a ceremony from an initial key
+    ///
+    /// The initial key is trusted by everybody, usually one where α and the discrete
+    /// logarithm of h are public knowledge or unknown to anyone
+    ///
+    pub fn new(initial: Pk<T>) -> Self {
+        let current = Pk {
+            g_powers: initial.g_powers.clone(),
+            h_powers: initial.h_powers.clone(),
+            g2_powers: initial.g2_powers.clone(),
+            h1: initial.h1,
+            alpha_g2: initial.alpha_g2
+        };
+        Transcript { initial, current, proofs: Vec::new() }
+    }
+
+    /// Adds a contribution with a fresh secret
+    pub fn contribute<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let (next, proof) = contribute(&self.current, rng);
+        self.current = next;
+        self.proofs.push(proof);
+    }
+
+    /// Verifies the whole chain of contributions
+    ///
+    /// Checks every Schnorr proof and pairing ratio from the initial key up to the current key,
+    /// and that the current key is a valid key
+    ///
+    /// # Errors
+    ///
+    /// * `KzgError::MalformedProof` if the keys differ in degree or hiding generator,
+    ///   or the last proof does not end at the current key
+    /// * `KzgError::InvalidEncoding` if the current key holds the identity or a point outside the subgroup
+    /// * `KzgError::SchnorrFailed` if a proof of knowledge is invalid
+    /// * `KzgError::PairingCheckFailed` if a pairing ratio fails or the current key is not a valid key
+    ///
+    pub fn verify(&self) -> Result<(), KzgError> {
+        if self.initial.g_powers.len() != self.current.g_powers.len() || self.initial.h1 != self.current.h1 {
+            return Err(KzgError::MalformedProof);
+        }
+
+        let mut alpha_g2 = self.initial.alpha_g2;
+        for proof in self.proofs.iter() {
+            check_step(&alpha_g2, proof)?;
+            alpha_g2 = proof.alpha_g2;
+        }
+        if alpha_g2 != self.current.alpha_g2 {
+            return Err(KzgError::MalformedProof);
+        }
+
+        self.current.verify()
+    }
+
+    /// The key the ceremony started from
+    pub fn initial(&self) -> &Pk<T> {
+        &self.initial
+    }
+
+    /// The key after the last contribution
+    pub fn current(&self) -> &Pk<T> {
+        &self.current
+    }
+
+    /// The proofs of the contributions, in order
+    pub fn proofs(&self) -> &[ContributionProof<T>] {
+        &self.proofs
+    }
+
+    /// Ends the ceremony, returning the final key
+    pub fn into_pk(self) -> Pk<T> {
+        self.current
+    }
+}
+
+
+// the Schnorr proof for g^τ and the ratio e(g^τ, g2^α) = e(g, g2^α')
+fn check_step<T: Curve>(alpha_g2: &T::G2, proof: &ContributionProof<T>) -> Result<(), KzgError> {
+    // τ = 0 would erase all earlier contributions
+    let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+    if proof.tau_g1 == identity {
+        return Err(KzgError::PairingCheckFailed);
+    }
+
+    // g^s · (g^τ)^c = g^k
+    let c = contribution_challenge::<T>(alpha_g2, &proof.alpha_g2, proof.tau_g1, proof.nonce);
+    let left = T::msm_g1(&[proof.response, c], &[T::g1(), proof.tau_g1]);
+    if left != proof.nonce {
+        return Err(KzgError::SchnorrFailed);
+    }
+
+    let pairs = [(proof.tau_g1, *alpha_g2), (T::g1sub(&identity, &T::g1()), proof.alpha_g2)];
+    if T::multi_pairing(&pairs) != T::gt_one() {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+// the Fiat-Shamir challenge of the Schnorr proof of a contribution
+// binds the proof to the keys before and after, so it cannot be replayed
+fn contribution_challenge<T: Curve>(previous: &T::G2, next: &T::G2, tau_g1: T::G1, nonce: T::G1) -> T::Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(b"kzg ceremony");
+
+    hasher.update(T::g2_to_bytes(previous));
+    hasher.update(T::g2_to_bytes(next));
+    hasher.update(T::g1_to_bytes(&tau_g1));
+    hasher.update(T::g1_to_bytes(&nonce));
+
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    T::scalar_from_bytes_wide(&bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+    use crate::{commitzk, queryzk, setup, verifyzk};
+    use super::*;
+
+    // this tests completeness of contributions and that the final key works with the protocol
+    #[quickcheck]
+    fn test_ceremony(participants: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let mut transcript = Transcript::new(setup::<Curve, _>(6, &mut rng));
+        for _ in 0..participants % 4 {
+            transcript.contribute(&mut rng);
+        }
+        if transcript.verify().is_err() {
+            return false;
+        }
+
+        let pk = transcript.into_pk();
+        let set: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let kj = Curve::scalar_from_literal(&4);
+        let proof = queryzk(&pk, &opening, kj, &mut rng).expect("opening is valid");
+        verifyzk(&pk, &commitment, kj, &proof).is_ok()
+    }
+
+    #[test]
+    fn test_invalid_contributions() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(4, &mut rng);
+        let (next, proof) = contribute(&pk, &mut rng);
+        assert!(verify_contribution(&pk, &next, &proof).is_ok());
+
+        // a proof of knowledge for a different τ
+        let mut forged = proof.clone();
+        forged.tau_g1 = Curve::g1add(&forged.tau_g1, &Curve::g1());
+        assert_eq!(verify_contribution(&pk, &next, &forged), Err(KzgError::SchnorrFailed));
+
+        // a key that is not made of powers
+        let (mut broken, proof) = contribute(&pk, &mut rng);
+        broken.g_powers[1] = Curve::g1add(&broken.g_powers[1], &Curve::g1());
+        assert_eq!(verify_contribution(&pk, &broken, &proof), Err(KzgError::PairingCheckFailed));
+
+        // a contribution on top of a different key does not chain
+        let (other, other_proof) = contribute(&next, &mut rng);
+        assert!(verify_contribution(&next, &other, &other_proof).is_ok());
+        assert!(verify_contribution(&pk, &other, &other_proof).is_err());
+    }
+
+    #[test]
+    fn test_transcript_chain() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let mut transcript = Transcript::new(setup::<Curve, _>(4, &mut rng));
+        transcript.contribute(&mut rng);
+        transcript.contribute(&mut rng);
+        assert!(transcript.verify().is_ok());
+
+        // dropping a contribution breaks the chain
+        transcript.proofs.remove(0);
+        assert!(transcript.verify().is_err());
+    }
+}
@@ -9,7 +9,8 @@
 //! The `Curve` trait defines the essential operations needed for the kzg commitment scheme
 //! - **Group operations**: Group operations for G1, G2 and Scalars
 //! - **Bilinear pairings**: pairings e(G1, G2) → GT are used for protocol verification
-//! - **Hash functions**: Hash makes the Schnorr proof non-interactive
+//! - **Hash functions**: Hash makes the Schnorr proof non-interactive, hashing to G1
+//!   gives generators whose discrete logarithm nobody knows
 //!
 //! ### SpecCurve
 //! A specification-friendly implementation using `hacspec_bls12_381`
@@ -21,6 +22,7 @@ use std::ops::{Add, Mul, Sub};
 use std::hash::Hash;
 use std::fmt::{Display, Debug};
 use sha2::{Sha256, Digest};
+use rand::{CryptoRng, RngCore};
 
 mod spec {
    pub use hacspec_bls12_381::*; 
@@ -29,19 +31,29 @@ mod spec {
 mod sha {
     pub use hacspec_sha256::*;
 }
+mod spec_hash {
+    pub use hacspec_bls12_381_hash::*;
+}
 use blstrs;
 
 pub trait Curve {
+    // Send and Sync so that the parallel feature can share elements between threads
     type G1:
         Eq +
         Copy + 
+        Send +
+        Sync +
         Debug;
     type G2:
         Eq +
         Copy + 
+        Send +
+        Sync +
         Debug;
     type Scalar:
         Eq +
+        Send +
+        Sync +
         Add<Output = Self::Scalar> +
         Sub<Output = Self::Scalar> +
         Mul<Output = Self::Scalar> +
@@ -57,9 +69,41 @@ pub trait Curve {
         Debug;
 
     fn scalar_from_literal(x: &u128) -> Self::Scalar;
+
+    // interprets 64 big endian bytes as an integer and reduces it modulo the group order
+    // 2^512 is much larger than the group order so the result is close to uniform
+    fn scalar_from_bytes_wide(bytes: &[u8; 64]) -> Self::Scalar {
+        // 2^128 = (2^128 - 1) + 1
+        let shift = Self::scalar_from_literal(&u128::MAX) + Self::scalar_from_literal(&1);
+
+        let mut result = Self::scalar_from_literal(&0);
+        for chunk in bytes.chunks(16) {
+            let limb = u128::from_be_bytes(chunk.try_into().expect("chunks have 16 bytes"));
+            result = result * shift + Self::scalar_from_literal(&limb);
+        }
+        result
+    }
+
+    // samples a uniformly random scalar from the full field
+    fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Self::Scalar {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        Self::scalar_from_bytes_wide(&bytes)
+    }
     
     fn scalar_pow(x: &Self::Scalar, y: &u128) -> Self::Scalar;
 
+    // inverts x using Fermat's little theorem x^(r - 2), the inverse of zero is zero
+    fn scalar_inv(x: &Self::Scalar) -> Self::Scalar {
+        // r - 2 split into two 128 bit halves
+        let high: u128 = 0x73eda753299d7d483339d80809a1d805;
+        let low: u128 = 0x53bda402fffe5bfefffffffeffffffff;
+
+        // x^(2^128)
+        let shifted = Self::scalar_pow(&Self::scalar_pow(x, &(1 << 64)), &(1 << 64));
+        Self::scalar_pow(&shifted, &high) * Self::scalar_pow(x, &low)
+    }
+
     fn g1mul(x: &Self::Scalar, y: &Self::G1) -> Self::G1;
     fn g2mul(x: &Self::Scalar, y: &Self::G2) -> Self::G2;
 
@@ -71,12 +115,108 @@ pub trait Curve {
     
     fn g1() -> Self::G1;
     fn g2() -> Self::G2;
+
+    // computes Σ scalars[i] · bases[i] using the bucket method of Pippenger
+    fn msm_g1(scalars: &[Self::Scalar], bases: &[Self::G1]) -> Self::G1 {
+        assert_eq!(scalars.len(), bases.len(), "msm needs one scalar per base");
+        let identity = Self::g1mul(&Self::scalar_from_literal(&0), &Self::g1());
+        let scalars: Vec<[u8; SCALAR_BYTES]> = scalars.iter().map(Self::scalar_to_bytes).collect();
+        pippenger(&scalars, bases, identity, Self::g1add)
+    }
+
+    // the same in G2
+    fn msm_g2(scalars: &[Self::Scalar], bases: &[Self::G2]) -> Self::G2 {
+        assert_eq!(scalars.len(), bases.len(), "msm needs one scalar per base");
+        let identity = Self::g2mul(&Self::scalar_from_literal(&0), &Self::g2());
+        let scalars: Vec<[u8; SCALAR_BYTES]> = scalars.iter().map(Self::scalar_to_bytes).collect();
+        pippenger(&scalars, bases, identity, Self::g2add)
+    }
+
+    // table[index], reading every entry so that the memory access does not depend on the index
+    // the fixed base tables of the parallel setup are indexed by the bits of the secret α^i
+    fn g1_lookup(table: &[Self::G1], index: usize) -> Self::G1;
+    fn g2_lookup(table: &[Self::G2], index: usize) -> Self::G2;
     
     fn pairing(x: &Self::G1, y: &Self::G2) -> Self::GT;
 
+    // computes the product of the pairings e(x_1, y_1) · ... · e(x_n, y_n)
+    // with a single final exponentiation
+    fn multi_pairing(pairs: &[(Self::G1, Self::G2)]) -> Self::GT;
+
+    // the identity of GT
+    fn gt_one() -> Self::GT;
+
     fn fiat_shamir_hash(z: Self::G1, n1: Self::G1, n2: Self::G1, h: Self::G1) -> Self::Scalar; 
+
+    // hashes msg to G1 with the RFC 9380 suite BLS12381G1_XMD:SHA-256_SSWU_RO_
+    // nobody knows the discrete logarithm of the result to any other point
+    fn hash_to_g1(dst: &[u8], msg: &[u8]) -> Self::G1;
+
+    // canonical encodings shared by all implementations
+    // points use the compressed zcash format and scalars are big endian
+    // decoding rejects non-canonical encodings and points outside the prime order subgroup
+    fn g1_to_bytes(x: &Self::G1) -> [u8; G1_BYTES];
+    fn g1_from_bytes(bytes: &[u8; G1_BYTES]) -> Option<Self::G1>;
+
+    fn g2_to_bytes(x: &Self::G2) -> [u8; G2_BYTES];
+    fn g2_from_bytes(bytes: &[u8; G2_BYTES]) -> Option<Self::G2>;
+
+    fn scalar_to_bytes(x: &Self::Scalar) -> [u8; SCALAR_BYTES];
+    fn scalar_from_bytes(bytes: &[u8; SCALAR_BYTES]) -> Option<Self::Scalar>;
+}
+
+// Σ scalars[i] · bases[i] for big endian scalars in any group, given by its identity and addition
+fn pippenger<P: Copy>(scalars: &[[u8; SCALAR_BYTES]], bases: &[P], identity: P, add: impl Fn(&P, &P) -> P) -> P {
+    const BITS: usize = 8 * SCALAR_BYTES;
+
+    // window size in bits, roughly log2(n) - 2
+    let log_n = (usize::BITS - scalars.len().leading_zeros()) as usize;
+    let window = log_n.saturating_sub(2).clamp(1, 16);
+
+    // the bits [start, start + window) of a big endian scalar
+    let digit = |bytes: &[u8; SCALAR_BYTES], start: usize| -> usize {
+        let mut digit = 0;
+        for bit in (start..(start + window).min(BITS)).rev() {
+            let byte = bytes[SCALAR_BYTES - 1 - bit / 8];
+            digit = (digit << 1) | ((byte >> (bit % 8)) & 1) as usize;
+        }
+        digit
+    };
+
+    let mut result = identity;
+    for start in (0..BITS).step_by(window).rev() {
+        for _ in 0..window {
+            result = add(&result, &result);
+        }
+
+        // bucket j collects the bases whose digit is j + 1
+        let mut buckets = vec![identity; (1 << window) - 1];
+        for (scalar, base) in scalars.iter().zip(bases.iter()) {
+            let digit = digit(scalar, start);
+            if digit != 0 {
+                buckets[digit - 1] = add(&buckets[digit - 1], base);
+            }
+        }
+
+        // Σ (j + 1) · bucket j as a sum of running sums
+        let mut running = identity;
+        let mut sum = identity;
+        for bucket in buckets.iter().rev() {
+            running = add(&running, bucket);
+            sum = add(&sum, &running);
+        }
+        result = add(&result, &sum);
+    }
+    result
 }
 
+/// Length of a compressed G1 point
+pub const G1_BYTES: usize = 48;
+/// Length of a compressed G2 point
+pub const G2_BYTES: usize = 96;
+/// Length of a scalar
+pub const SCALAR_BYTES: usize = 32;
+
 
 
 fn g1_to_byte_seq(g: spec::G1) -> hacspec_lib::ByteSeq {
@@ -94,8 +234,241 @@ fn g1_to_byte_seq(g: spec::G1) -> hacspec_lib::ByteSeq {
 }
 
 
+// compressed point encodings for the specification curve
+// these follow the zcash serialization format that blstrs uses
+// so both implementations produce identical bytes
+
+const P_HEX: &str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+const R_HEX: &str = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+// (p - 1) / 2, values above this are lexicographically largest
+const HALF_P_HEX: &str = "0d0088f51cbff34d258dd3db21a5d66bb23ba5c279c2895fb39869507b587b120f55ffff58a9ffffdcff7fffffffd555";
+// (p + 1) / 4, the square root exponent since p = 3 mod 4
+const SQRT_EXP_HEX: &str = "0680447a8e5ff9a692c6e9ed90d2eb35d91dd2e13ce144afd9cc34a83dac3d8907aaffffac54ffffee7fbfffffffeaab";
+// (p - 3) / 4, used by the square root in Fp2
+const SQRT2_EXP_HEX: &str = "0680447a8e5ff9a692c6e9ed90d2eb35d91dd2e13ce144afd9cc34a83dac3d8907aaffffac54ffffee7fbfffffffeaaa";
+
+const COMPRESSED_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+const SORT_FLAG: u8 = 0x20;
+
+fn hex_to_bytes<const N: usize>(hex: &str) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    for i in 0..N {
+        bytes[i] = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).expect("invalid hex constant");
+    }
+    bytes
+}
+
+// square and multiply with a big endian exponent
+fn pow_be<F: Copy>(base: F, exp: &[u8], one: F, mul: impl Fn(F, F) -> F) -> F {
+    let mut result = one;
+    for byte in exp {
+        for bit in (0..8).rev() {
+            result = mul(result, result);
+            if (byte >> bit) & 1 == 1 {
+                result = mul(result, base);
+            }
+        }
+    }
+    result
+}
+
+fn fp_to_bytes(x: &spec::Fp) -> [u8; 48] {
+    let bytes = x.to_be_bytes();
+    let mut result = [0u8; 48];
+    result[48 - bytes.len()..].copy_from_slice(&bytes);
+    result
+}
+
+// rejects encodings of integers that are not reduced modulo p
+fn fp_from_bytes(bytes: &[u8; 48]) -> Option<spec::Fp> {
+    if bytes[..] >= hex_to_bytes::<48>(P_HEX)[..] {
+        return None;
+    }
+    Some(spec::Fp::from_byte_seq_be(&hacspec_lib::ByteSeq::from_public_slice(bytes)))
+}
+
+fn fp_is_largest(x: &spec::Fp) -> bool {
+    fp_to_bytes(x)[..] > hex_to_bytes::<48>(HALF_P_HEX)[..]
+}
+
+fn fp_sqrt(a: spec::Fp) -> Option<spec::Fp> {
+    let one = spec::Fp::from_literal(1);
+    let root = pow_be(a, &hex_to_bytes::<48>(SQRT_EXP_HEX), one, |x, y| x * y);
+    
+    if root * root == a {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+type Fp2 = (spec::Fp, spec::Fp);
+
+fn fp2_mul(x: Fp2, y: Fp2) -> Fp2 {
+    let (x0, x1) = x;
+    let (y0, y1) = y;
+    (x0 * y0 - x1 * y1, x0 * y1 + x1 * y0)
+}
+
+fn fp2_is_largest(x: &Fp2) -> bool {
+    let zero = spec::Fp::from_literal(0);
+    let (x0, x1) = x;
+    if *x1 != zero {
+        fp_is_largest(x1)
+    } else {
+        fp_is_largest(x0)
+    }
+}
+
+// algorithm 9 of https://eprint.iacr.org/2012/685.pdf
+fn fp2_sqrt(a: Fp2) -> Option<Fp2> {
+    let zero = spec::Fp::from_literal(0);
+    let one = (spec::Fp::from_literal(1), zero);
+    let minus_one = (zero - spec::Fp::from_literal(1), zero);
+
+    let a1 = pow_be(a, &hex_to_bytes::<48>(SQRT2_EXP_HEX), one, fp2_mul);
+    let alpha = fp2_mul(fp2_mul(a1, a1), a);
+    let x0 = fp2_mul(a1, a);
+
+    let root = if alpha == minus_one {
+        // multiply by i
+        let (c0, c1) = x0;
+        (zero - c1, c0)
+    } else {
+        let (alpha0, alpha1) = alpha;
+        let b = pow_be((alpha0 + spec::Fp::from_literal(1), alpha1), &hex_to_bytes::<48>(HALF_P_HEX), one, fp2_mul);
+        fp2_mul(b, x0)
+    };
+
+    if fp2_mul(root, root) == a {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+// checks [r]P = 0 by computing [r - 1]P + P
+fn spec_g1_in_subgroup(p: spec::G1) -> bool {
+    let r_minus_one = spec::Scalar::from_literal(0) - spec::Scalar::from_literal(1);
+    let (_, _, inf) = spec::g1add(spec::g1mul(r_minus_one, p), p);
+    inf
+}
+
+fn spec_g2_in_subgroup(p: spec::G2) -> bool {
+    let r_minus_one = spec::Scalar::from_literal(0) - spec::Scalar::from_literal(1);
+    let (_, _, inf) = spec::g2add(spec::g2mul(r_minus_one, p), p);
+    inf
+}
+
+// returns None if the flags are malformed
+// otherwise the remaining bytes and whether the point is the identity and the sort flag
+fn split_flags<const N: usize>(bytes: &[u8; N]) -> Option<([u8; N], bool, bool)> {
+    let flags = bytes[0];
+    if flags & COMPRESSED_FLAG == 0 {
+        return None;
+    }
+    let infinity = flags & INFINITY_FLAG != 0;
+    let sort = flags & SORT_FLAG != 0;
+
+    let mut rest = *bytes;
+    rest[0] &= !(COMPRESSED_FLAG | INFINITY_FLAG | SORT_FLAG);
+
+    // the identity is encoded with all other bits set to zero
+    if infinity && (sort || rest.iter().any(|b| *b != 0)) {
+        return None;
+    }
+    Some((rest, infinity, sort))
+}
+
+fn spec_g1_to_bytes(p: &spec::G1) -> [u8; 48] {
+    let (x, y, inf) = p;
+    if *inf {
+        let mut bytes = [0u8; 48];
+        bytes[0] = COMPRESSED_FLAG | INFINITY_FLAG;
+        return bytes;
+    }
+
+    let mut bytes = fp_to_bytes(x);
+    bytes[0] |= COMPRESSED_FLAG;
+    if fp_is_largest(y) {
+        bytes[0] |= SORT_FLAG;
+    }
+    bytes
+}
+
+fn spec_g1_from_bytes(bytes: &[u8; 48]) -> Option<spec::G1> {
+    let (x_bytes, infinity, sort) = split_flags(bytes)?;
+    let zero = spec::Fp::from_literal(0);
+    if infinity {
+        return Some((zero, zero, true));
+    }
+
+    // y^2 = x^3 + 4
+    let x = fp_from_bytes(&x_bytes)?;
+    let mut y = fp_sqrt(x * x * x + spec::Fp::from_literal(4))?;
+    if fp_is_largest(&y) != sort {
+        y = zero - y;
+    }
+
+    let p = (x, y, false);
+    if !spec_g1_in_subgroup(p) {
+        return None;
+    }
+    Some(p)
+}
+
+fn spec_g2_to_bytes(p: &spec::G2) -> [u8; 96] {
+    let ((x0, x1), y, inf) = p;
+    let mut bytes = [0u8; 96];
+    if *inf {
+        bytes[0] = COMPRESSED_FLAG | INFINITY_FLAG;
+        return bytes;
+    }
+
+    // the imaginary part comes first
+    bytes[..48].copy_from_slice(&fp_to_bytes(x1));
+    bytes[48..].copy_from_slice(&fp_to_bytes(x0));
+    bytes[0] |= COMPRESSED_FLAG;
+    if fp2_is_largest(y) {
+        bytes[0] |= SORT_FLAG;
+    }
+    bytes
+}
+
+fn spec_g2_from_bytes(bytes: &[u8; 96]) -> Option<spec::G2> {
+    let (x_bytes, infinity, sort) = split_flags(bytes)?;
+    let zero = spec::Fp::from_literal(0);
+    if infinity {
+        return Some(((zero, zero), (zero, zero), true));
+    }
+
+    let x1 = fp_from_bytes(&x_bytes[..48].try_into().expect("slice of 48 bytes"))?;
+    let x0 = fp_from_bytes(&x_bytes[48..].try_into().expect("slice of 48 bytes"))?;
+    let x = (x0, x1);
+
+    // y^2 = x^3 + 4(1 + i)
+    let (c0, c1) = fp2_mul(fp2_mul(x, x), x);
+    let four = spec::Fp::from_literal(4);
+    let mut y = fp2_sqrt((c0 + four, c1 + four))?;
+    if fp2_is_largest(&y) != sort {
+        let (y0, y1) = y;
+        y = (zero - y0, zero - y1);
+    }
+
+    let p = (x, y, false);
+    if !spec_g2_in_subgroup(p) {
+        return None;
+    }
+    Some(p)
+}
+
 
+
+// the derives let the protocol types that are generic over the curve derive them as well
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct FastCurve;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SpecCurve;
 
 
@@ -134,6 +507,13 @@ impl Curve for SpecCurve {
     (spec::Fp::from_hex("17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
      spec::Fp::from_hex("08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"), false)
     }
+    // the specification makes no timing guarantees, g1mul branches on the bits of the scalar too
+    fn g1_lookup(table: &[Self::G1], index: usize) -> Self::G1 {
+        table[index]
+    }
+    fn g2_lookup(table: &[Self::G2], index: usize) -> Self::G2 {
+        table[index]
+    }
     fn g2() -> Self::G2 {
     ((spec::Fp::from_hex("24aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"),
       spec::Fp::from_hex("13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e")),
@@ -143,6 +523,24 @@ impl Curve for SpecCurve {
     fn pairing(x: &Self::G1, y: &Self::G2) -> Self::GT {
         spec::pairing(x.clone(), y.clone())
     }
+    // the specification has no separate miller loop, so this is a plain product of pairings
+    fn multi_pairing(pairs: &[(Self::G1, Self::G2)]) -> Self::GT {
+        let mut result = Self::gt_one();
+        for (x, y) in pairs {
+            // pairings with the identity are one
+            if x.2 || y.2 {
+                continue;
+            }
+            result = spec::fp12mul(result, spec::pairing(x.clone(), y.clone()));
+        }
+        result
+    }
+    fn gt_one() -> Self::GT {
+        let zero = spec::Fp::from_literal(0);
+        let one = spec::Fp::from_literal(1);
+        let zero2 = (zero, zero);
+        (((one, zero), zero2, zero2), (zero2, zero2, zero2))
+    }
     
     fn fiat_shamir_hash(z: Self::G1, n1: Self::G1, n2: Self::G1, h: Self::G1) -> Self::Scalar {
         let g = g1_to_byte_seq(Self::g1());
@@ -157,12 +555,45 @@ impl Curve for SpecCurve {
 
         spec::Scalar::from_byte_seq_be(&digest)
     } 
+
+    fn hash_to_g1(dst: &[u8], msg: &[u8]) -> Self::G1 {
+        let msg = hacspec_lib::ByteSeq::from_public_slice(msg);
+        let dst = hacspec_lib::ByteSeq::from_public_slice(dst);
+        spec_hash::g1_hash_to_curve_sswu(&msg, &dst)
+    }
+
+    fn g1_to_bytes(x: &Self::G1) -> [u8; G1_BYTES] {
+        spec_g1_to_bytes(x)
+    }
+    fn g1_from_bytes(bytes: &[u8; G1_BYTES]) -> Option<Self::G1> {
+        spec_g1_from_bytes(bytes)
+    }
+    fn g2_to_bytes(x: &Self::G2) -> [u8; G2_BYTES] {
+        spec_g2_to_bytes(x)
+    }
+    fn g2_from_bytes(bytes: &[u8; G2_BYTES]) -> Option<Self::G2> {
+        spec_g2_from_bytes(bytes)
+    }
+    fn scalar_to_bytes(x: &Self::Scalar) -> [u8; SCALAR_BYTES] {
+        let bytes = x.to_be_bytes();
+        let mut result = [0u8; SCALAR_BYTES];
+        result[SCALAR_BYTES - bytes.len()..].copy_from_slice(&bytes);
+        result
+    }
+    fn scalar_from_bytes(bytes: &[u8; SCALAR_BYTES]) -> Option<Self::Scalar> {
+        if bytes[..] >= hex_to_bytes::<SCALAR_BYTES>(R_HEX)[..] {
+            return None;
+        }
+        Some(spec::Scalar::from_byte_seq_be(&hacspec_lib::ByteSeq::from_public_slice(bytes)))
+    }
 }
 
 
 
 use blstrs::G1Projective;
 use group::{ff::{Field, PrimeField}, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
 
 impl Curve for FastCurve {
     type G1 = blstrs::G1Projective;
@@ -179,6 +610,9 @@ impl Curve for FastCurve {
         let exp = vec![small_end, big_end];
         x.pow(exp)
     }
+    fn scalar_inv(x: &Self::Scalar) -> Self::Scalar {
+        x.invert().unwrap_or(blstrs::Scalar::ZERO)
+    }
     fn g1mul(x: &Self::Scalar, y: &Self::G1) -> Self::G1 {
         y.mul(x)
     }
@@ -203,11 +637,56 @@ impl Curve for FastCurve {
     fn g2() -> Self::G2 {
        blstrs::G2Projective::generator() 
     }
+    fn msm_g1(scalars: &[Self::Scalar], bases: &[Self::G1]) -> Self::G1 {
+        assert_eq!(scalars.len(), bases.len(), "msm needs one scalar per base");
+        // blst does not accept an empty msm
+        if bases.is_empty() {
+            return blstrs::G1Projective::identity();
+        }
+        blstrs::G1Projective::multi_exp(bases, scalars)
+    }
+    fn msm_g2(scalars: &[Self::Scalar], bases: &[Self::G2]) -> Self::G2 {
+        assert_eq!(scalars.len(), bases.len(), "msm needs one scalar per base");
+        if bases.is_empty() {
+            return blstrs::G2Projective::identity();
+        }
+        blstrs::G2Projective::multi_exp(bases, scalars)
+    }
+    fn g1_lookup(table: &[Self::G1], index: usize) -> Self::G1 {
+        let mut result = blstrs::G1Projective::identity();
+        for (j, entry) in table.iter().enumerate() {
+            result.conditional_assign(entry, (j as u64).ct_eq(&(index as u64)));
+        }
+        result
+    }
+    fn g2_lookup(table: &[Self::G2], index: usize) -> Self::G2 {
+        let mut result = blstrs::G2Projective::identity();
+        for (j, entry) in table.iter().enumerate() {
+            result.conditional_assign(entry, (j as u64).ct_eq(&(index as u64)));
+        }
+        result
+    }
     fn pairing(x: &Self::G1, y: &Self::G2) -> Self::GT {
         let left = blstrs::G1Affine::from(x);
         let right = blstrs::G2Affine::from(y);
         blstrs::pairing(&left, &right)
     }
+    fn multi_pairing(pairs: &[(Self::G1, Self::G2)]) -> Self::GT {
+        // the miller loop does not accept an empty list of terms
+        if pairs.is_empty() {
+            return Self::gt_one();
+        }
+
+        let prepared: Vec<(blstrs::G1Affine, blstrs::G2Prepared)> = pairs.iter()
+            .map(|(x, y)| (blstrs::G1Affine::from(x), blstrs::G2Prepared::from(blstrs::G2Affine::from(y))))
+            .collect();
+        let terms: Vec<(&blstrs::G1Affine, &blstrs::G2Prepared)> = prepared.iter().map(|(x, y)| (x, y)).collect();
+
+        blstrs::Bls12::multi_miller_loop(&terms).final_exponentiation()
+    }
+    fn gt_one() -> Self::GT {
+        blstrs::Gt::identity()
+    }
     
     fn fiat_shamir_hash(z: Self::G1, n1: Self::G1, n2: Self::G1, h: Self::G1) -> Self::Scalar {
         let mut hasher = Sha256::new();
@@ -226,6 +705,30 @@ impl Curve for FastCurve {
         blstrs::Scalar::from_bytes_be(&res).expect("could not create scalar from hash")
     }
 
+    fn hash_to_g1(dst: &[u8], msg: &[u8]) -> Self::G1 {
+        G1Projective::hash_to_curve(msg, dst, &[])
+    }
+
+    fn g1_to_bytes(x: &Self::G1) -> [u8; G1_BYTES] {
+        x.to_compressed()
+    }
+    fn g1_from_bytes(bytes: &[u8; G1_BYTES]) -> Option<Self::G1> {
+        // checks that the point is on the curve and in the subgroup
+        Option::from(blstrs::G1Projective::from_compressed(bytes))
+    }
+    fn g2_to_bytes(x: &Self::G2) -> [u8; G2_BYTES] {
+        x.to_compressed()
+    }
+    fn g2_from_bytes(bytes: &[u8; G2_BYTES]) -> Option<Self::G2> {
+        Option::from(blstrs::G2Projective::from_compressed(bytes))
+    }
+    fn scalar_to_bytes(x: &Self::Scalar) -> [u8; SCALAR_BYTES] {
+        x.to_bytes_be()
+    }
+    fn scalar_from_bytes(bytes: &[u8; SCALAR_BYTES]) -> Option<Self::Scalar> {
+        Option::from(blstrs::Scalar::from_bytes_be(bytes))
+    }
+
 }
 
 
@@ -233,19 +736,8 @@ impl Curve for FastCurve {
 mod test {
     use quickcheck_macros::quickcheck;
 
-    use rand::random;
     use super::*;
-
-    // generates n random u128
-    // lets us avoid generating randomness directly in our kzg implementation
-    fn generate_randomness(n: usize) -> Vec<u128> {
-        let mut rand: Vec<u128> = Vec::with_capacity(n); 
-
-        for _ in 0..n {
-            rand.push(random())
-    }
-    rand
-    }
+    use crate::tape::generate_randomness;
 
     #[quickcheck]
     fn test_trait_scalar_from_literal(base: u128) -> bool { 
@@ -273,6 +765,190 @@ mod test {
         spec == fast
     } 
 
+    #[quickcheck]
+    fn test_trait_scalar_from_bytes_wide(a: u128, b: u128, c: u128, d: u128) -> bool { 
+        let mut bytes = [0u8; 64];
+        for (i, limb) in [a, b, c, d].iter().enumerate() {
+            bytes[16 * i..16 * (i + 1)].copy_from_slice(&limb.to_be_bytes());
+        }
+
+        let specscalar = SpecCurve::scalar_from_bytes_wide(&bytes);
+        let fastscalar = FastCurve::scalar_from_bytes_wide(&bytes);
+        
+        let spec = specscalar.to_be_bytes();
+        let fast = fastscalar.to_bytes_be().to_vec();
+
+        spec == fast
+    } 
+
+    #[quickcheck]
+    fn test_trait_scalar_inv(x: u128) -> bool {
+        let specscalar = SpecCurve::scalar_from_literal(&x);
+        let fastscalar = FastCurve::scalar_from_literal(&x);
+
+        let spec = SpecCurve::scalar_inv(&specscalar);
+        let fast = FastCurve::scalar_inv(&fastscalar);
+
+        let expected = if x == 0 { 0 } else { 1 };
+        spec.to_be_bytes() == fast.to_bytes_be().to_vec()
+            && spec * specscalar == SpecCurve::scalar_from_literal(&expected)
+    }
+
+    #[quickcheck]
+    fn test_trait_msm_g1(xs: Vec<(u128, u128)>) -> bool {
+        let mut spec_expected = SpecCurve::g1mul(&SpecCurve::scalar_from_literal(&0), &SpecCurve::g1());
+        let mut fast_expected = FastCurve::g1mul(&FastCurve::scalar_from_literal(&0), &FastCurve::g1());
+        let mut spec_scalars = Vec::new();
+        let mut spec_bases = Vec::new();
+        let mut fast_scalars = Vec::new();
+        let mut fast_bases = Vec::new();
+
+        for (scalar, base) in xs.iter().take(8) {
+            // use the full scalar range so every window is exercised
+            let spec_scalar = SpecCurve::scalar_inv(&SpecCurve::scalar_from_literal(scalar));
+            let spec_base = SpecCurve::g1mul(&SpecCurve::scalar_from_literal(base), &SpecCurve::g1());
+            spec_expected = SpecCurve::g1add(&spec_expected, &SpecCurve::g1mul(&spec_scalar, &spec_base));
+            spec_scalars.push(spec_scalar);
+            spec_bases.push(spec_base);
+
+            let fast_scalar = FastCurve::scalar_inv(&FastCurve::scalar_from_literal(scalar));
+            let fast_base = FastCurve::g1mul(&FastCurve::scalar_from_literal(base), &FastCurve::g1());
+            fast_expected = FastCurve::g1add(&fast_expected, &FastCurve::g1mul(&fast_scalar, &fast_base));
+            fast_scalars.push(fast_scalar);
+            fast_bases.push(fast_base);
+        }
+
+        let spec = SpecCurve::msm_g1(&spec_scalars, &spec_bases);
+        let fast = FastCurve::msm_g1(&fast_scalars, &fast_bases);
+
+        spec == spec_expected
+            && fast == fast_expected
+            && SpecCurve::g1_to_bytes(&spec) == FastCurve::g1_to_bytes(&fast)
+    }
+
+    #[quickcheck]
+    fn test_trait_msm_g2(xs: Vec<(u128, u128)>) -> bool {
+        let mut spec_expected = SpecCurve::g2mul(&SpecCurve::scalar_from_literal(&0), &SpecCurve::g2());
+        let mut fast_expected = FastCurve::g2mul(&FastCurve::scalar_from_literal(&0), &FastCurve::g2());
+        let mut spec_scalars = Vec::new();
+        let mut spec_bases = Vec::new();
+        let mut fast_scalars = Vec::new();
+        let mut fast_bases = Vec::new();
+
+        for (scalar, base) in xs.iter().take(4) {
+            let spec_scalar = SpecCurve::scalar_inv(&SpecCurve::scalar_from_literal(scalar));
+            let spec_base = SpecCurve::g2mul(&SpecCurve::scalar_from_literal(base), &SpecCurve::g2());
+            spec_expected = SpecCurve::g2add(&spec_expected, &SpecCurve::g2mul(&spec_scalar, &spec_base));
+            spec_scalars.push(spec_scalar);
+            spec_bases.push(spec_base);
+
+            let fast_scalar = FastCurve::scalar_inv(&FastCurve::scalar_from_literal(scalar));
+            let fast_base = FastCurve::g2mul(&FastCurve::scalar_from_literal(base), &FastCurve::g2());
+            fast_expected = FastCurve::g2add(&fast_expected, &FastCurve::g2mul(&fast_scalar, &fast_base));
+            fast_scalars.push(fast_scalar);
+            fast_bases.push(fast_base);
+        }
+
+        let spec = SpecCurve::msm_g2(&spec_scalars, &spec_bases);
+        let fast = FastCurve::msm_g2(&fast_scalars, &fast_bases);
+
+        spec == spec_expected
+            && fast == fast_expected
+            && SpecCurve::g2_to_bytes(&spec) == FastCurve::g2_to_bytes(&fast)
+    }
+
+    #[quickcheck]
+    fn test_trait_lookup(index: u8) -> bool {
+        let index = (index % 16) as usize;
+        let g1_table: Vec<_> = (0..16).map(|j| FastCurve::g1mul(&FastCurve::scalar_from_literal(&j), &FastCurve::g1())).collect();
+        let g2_table: Vec<_> = (0..16).map(|j| FastCurve::g2mul(&FastCurve::scalar_from_literal(&j), &FastCurve::g2())).collect();
+
+        FastCurve::g1_lookup(&g1_table, index) == g1_table[index]
+            && FastCurve::g2_lookup(&g2_table, index) == g2_table[index]
+    }
+
+    #[quickcheck]
+    fn test_trait_multi_pairing(a: u128, b: u128, c: u128) -> bool {
+        let spec_terms = [
+            (SpecCurve::g1mul(&SpecCurve::scalar_from_literal(&a), &SpecCurve::g1()), SpecCurve::g2()),
+            (SpecCurve::g1(), SpecCurve::g2mul(&SpecCurve::scalar_from_literal(&b), &SpecCurve::g2())),
+            (SpecCurve::g1mul(&SpecCurve::scalar_from_literal(&0), &SpecCurve::g1()), SpecCurve::g2())
+        ];
+        let fast_terms = [
+            (FastCurve::g1mul(&FastCurve::scalar_from_literal(&a), &FastCurve::g1()), FastCurve::g2()),
+            (FastCurve::g1(), FastCurve::g2mul(&FastCurve::scalar_from_literal(&b), &FastCurve::g2())),
+            (FastCurve::g1mul(&FastCurve::scalar_from_literal(&0), &FastCurve::g1()), FastCurve::g2())
+        ];
+
+        // e(a·g, g2) · e(g, b·g2) = e((a + b)·g, g2)
+        let spec_expected = SpecCurve::pairing(&SpecCurve::g1mul(&(SpecCurve::scalar_from_literal(&a) + SpecCurve::scalar_from_literal(&b)), &SpecCurve::g1()), &SpecCurve::g2());
+        let fast_expected = FastCurve::pairing(&FastCurve::g1mul(&(FastCurve::scalar_from_literal(&a) + FastCurve::scalar_from_literal(&b)), &FastCurve::g1()), &FastCurve::g2());
+
+        // e(c·g, g2) · e(-c·g, g2) = 1
+        let fast_cancel = [
+            (FastCurve::g1mul(&FastCurve::scalar_from_literal(&c), &FastCurve::g1()), FastCurve::g2()),
+            (FastCurve::g1mul(&(FastCurve::scalar_from_literal(&0) - FastCurve::scalar_from_literal(&c)), &FastCurve::g1()), FastCurve::g2())
+        ];
+
+        SpecCurve::multi_pairing(&spec_terms) == spec_expected
+            && FastCurve::multi_pairing(&fast_terms) == fast_expected
+            && FastCurve::multi_pairing(&fast_cancel) == FastCurve::gt_one()
+            && SpecCurve::multi_pairing(&[]) == SpecCurve::gt_one()
+    }
+
+    #[quickcheck]
+    fn test_trait_point_encoding(x: u128) -> bool {
+        let spec_g1 = SpecCurve::g1mul(&SpecCurve::scalar_from_literal(&x), &SpecCurve::g1());
+        let fast_g1 = FastCurve::g1mul(&FastCurve::scalar_from_literal(&x), &FastCurve::g1());
+        let spec_g2 = SpecCurve::g2mul(&SpecCurve::scalar_from_literal(&x), &SpecCurve::g2());
+        let fast_g2 = FastCurve::g2mul(&FastCurve::scalar_from_literal(&x), &FastCurve::g2());
+
+        let g1_bytes = SpecCurve::g1_to_bytes(&spec_g1);
+        let g2_bytes = SpecCurve::g2_to_bytes(&spec_g2);
+        
+        g1_bytes == FastCurve::g1_to_bytes(&fast_g1)
+            && g2_bytes == FastCurve::g2_to_bytes(&fast_g2)
+            && SpecCurve::g1_from_bytes(&g1_bytes) == Some(spec_g1)
+            && SpecCurve::g2_from_bytes(&g2_bytes) == Some(spec_g2)
+    } 
+
+    #[test]
+    fn test_generator_encoding() {
+        let g1 = hex_to_bytes::<G1_BYTES>("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        assert_eq!(SpecCurve::g1_to_bytes(&SpecCurve::g1()), g1);
+        assert_eq!(FastCurve::g1_to_bytes(&FastCurve::g1()), g1);
+
+        // the identity
+        let mut identity = [0u8; G1_BYTES];
+        identity[0] = 0xc0;
+        let spec_identity = SpecCurve::g1sub(&SpecCurve::g1(), &SpecCurve::g1());
+        assert_eq!(SpecCurve::g1_to_bytes(&spec_identity), identity);
+        assert_eq!(SpecCurve::g1_from_bytes(&identity), Some(spec_identity));
+        assert_eq!(FastCurve::g1_from_bytes(&identity), Some(FastCurve::g1sub(&FastCurve::g1(), &FastCurve::g1())));
+
+        // x = 0 gives the point (0, 2) of order 3 which is outside the subgroup
+        let mut torsion = [0u8; G1_BYTES];
+        torsion[0] = 0x80;
+        assert_eq!(SpecCurve::g1_from_bytes(&torsion), None);
+        assert_eq!(FastCurve::g1_from_bytes(&torsion), None);
+    }
+
+    // the BLS12381G1_XMD:SHA-256_SSWU_RO_ vectors of RFC 9380, appendix J.9.1
+    #[test]
+    fn test_hash_to_g1() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let vectors = [
+            (&b""[..], "852926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1"),
+            (&b"abc"[..], "83567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903")
+        ];
+
+        for (msg, expected) in vectors {
+            let expected = hex_to_bytes::<G1_BYTES>(expected);
+            assert_eq!(SpecCurve::g1_to_bytes(&SpecCurve::hash_to_g1(dst, msg)), expected);
+            assert_eq!(FastCurve::g1_to_bytes(&FastCurve::hash_to_g1(dst, msg)), expected);
+        }
+    }
+
     #[quickcheck]
     fn test_trait_commitment() -> bool {
         use std::collections::HashSet;
@@ -282,34 +958,34 @@ mod test {
         let degree = poly.len() + 2;
         
         // we use the same random values for both implemtations
-        let fast_random = generate_randomness(poly.len() + 5);
+        let fast_random = generate_randomness(4 * (poly.len() + 5));
         let spec_random = fast_random.clone();
 
 
         // commiting to the fast implementation
-        let mut random = fast_random;
+        let mut random = crate::tape::Tape::new(fast_random);
         let pk = crate::setup::<FastCurve>(degree as u128, &mut random);
         let mut set = HashSet::new();
         for i in poly.iter() {
             set.insert(FastCurve::scalar_from_literal(i)); 
         } 
-        let (fast_commitment, _, _) = crate::commitzk(&pk, &set, &mut random);
-        let fast_commitment = blstrs::G1Affine::from(fast_commitment);
+        let (fast_commitment, _) = crate::commitzk(&pk, &set, &mut random).expect("set fits the public key");
+        let fast_commitment = blstrs::G1Affine::from(fast_commitment.0);
         
         
         //commiting to the specification implementation
-        let mut random = spec_random;
+        let mut random = crate::tape::Tape::new(spec_random);
         let pk = crate::setup::<SpecCurve>(degree as u128, &mut random);
         let mut set = HashSet::new();
         for i in poly.iter() {
             set.insert(SpecCurve::scalar_from_literal(i)); 
         }
-        let (spec_commitment, _, _) = crate::commitzk(&pk, &set, &mut random); 
+        let (spec_commitment, _) = crate::commitzk(&pk, &set, &mut random).expect("set fits the public key");
         
         
         // since the bls implementations should be equivalent and we use the same randomness
         // we expect the commitment to be the same
-        let (s_x, s_y, _) = spec_commitment; 
+        let (s_x, s_y, _) = spec_commitment.0; 
         let x_eq = s_x.to_be_bytes() == fast_commitment.x().to_bytes_be();
         let y_eq = s_y.to_be_bytes() == fast_commitment.y().to_bytes_be();
 
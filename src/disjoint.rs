@@ -0,0 +1,224 @@
+//! # Disjointness Proofs
+//!
+//! Proofs that none of the elements of a public list is in the committed set.
+//!
+//! The set polynomial φ(x) and Z(x) = ∏(x - t) have no common root exactly when
+//! gcd(φ, Z) = 1, that is when there are Bézout coefficients with a·φ + b·Z = 1.
+//! The prover blinds them with a random scalar ρ, a' = a + ρ·Z and b' = b - ρ·φ,
+//! and splits phi_hat·a' = q_hat·Z + r_hat. It publishes
+//! * A = g2^a'(α), computed from the powers of α in G2
+//! * B = g^b'(α) · h^-q_hat(α)
+//! * R = h^r_hat(α) with a Schnorr proof of knowledge of the coefficients of r_hat
+//!
+//! The verifier checks e(C, A) · e(B, g2^Z(α)) = e(g · R, g2), the pairing check
+//! of `verifyeval` with the evaluation 1 = a'·φ + b'·Z in place of φ(kj).
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use kzg::{setup, commitzk, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::disjoint::{prove_disjoint, verify_disjoint};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let set: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+//!
+//! let sanctioned: Vec<_> = [4, 5].iter().map(FastCurve::scalar_from_literal).collect();
+//! let proof = prove_disjoint(&pk, &opening, &sanctioned, &mut rng).expect("no element is in the set");
+//! assert!(verify_disjoint(&pk, &commitment, &sanctioned, &proof).is_ok());
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{check_batch, check_opening, commit_pair, commit_poly, commit_poly_g2, poly, prove_hidden_polynomial, transcript_challenge, verify_hidden_polynomial};
+use crate::{Commitment, KzgError, Opening, Pk};
+
+
+/// Proof that no element of a list is in the committed set
+///
+/// # Fields
+///
+/// * `bezout_a` - The commitment g2^a'(α) to the blinded Bézout coefficient of φ
+/// * `bezout_b` - The commitment g^b'(α) · h^-q_hat(α) to the blinded Bézout coefficient of Z
+/// * `remainder` - The remainder commitment R = h^r_hat(α)
+/// * `nonce` - The Schnorr nonce h^k(α) for a random k(x) of the same degree as r_hat(x)
+/// * `response` - The Schnorr response k(x) - c · r_hat(x), one coefficient per element
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisjointnessProof<T: Curve> {
+    pub bezout_a: T::G2,
+    pub bezout_b: T::G1,
+    pub remainder: T::G1,
+    pub nonce: T::G1,
+    pub response: Vec<T::Scalar>
+}
+
+
+/// Generates a proof that no element of `elements` is in the committed set
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `elements` - The distinct elements, in any order
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// A `DisjointnessProof<T>` for the elements
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::ElementInSet` if one of the elements is in the set
+///
+pub fn prove_disjoint<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening: &Opening<T>, elements: &[T::Scalar], rng: &mut R) -> Result<DisjointnessProof<T>, KzgError> {
+    check_opening(opening)?;
+    check_batch::<T>(pk, elements)?;
+
+    let vanishing = poly::roots_polynomial::<T>(elements);
+    let (a, b) = poly::bezout::<T>(&opening.phi, &vanishing).ok_or(KzgError::ElementInSet)?;
+
+    // a' = a + ρ·Z and b' = b - ρ·φ still satisfy a'·φ + b'·Z = 1
+    let blinding = T::random_scalar(rng);
+    let zero = T::scalar_from_literal(&0);
+    let a = poly::subtract::<T>(&a, &poly::product::<T>(&vec![zero - blinding], &vanishing));
+    let b = poly::subtract::<T>(&b, &poly::product::<T>(&vec![blinding], &opening.phi));
+
+    // phi_hat·a' = q_hat·Z + r_hat
+    let (hiding_quotient, hiding_remainder) = poly::divide::<T>(&poly::product::<T>(&opening.phi_hat, &a), &vanishing);
+
+    let bezout_a = commit_poly_g2::<T>(&a, &pk.g2_powers)?;
+    let bezout_b = T::g1sub(&commit_poly::<T>(&b, &pk.g_powers)?, &commit_poly::<T>(&hiding_quotient, &pk.h_powers)?);
+
+    // the challenge is bound to the commitment the proof is checked against
+    let commitment = commit_pair::<T>(pk, &opening.phi, &opening.phi_hat)?;
+    let (remainder, nonce, response) = prove_hidden_polynomial(pk, &hiding_remainder, |remainder, nonce| {
+        transcript_challenge::<T>(b"kzg disjointness", elements, &[pk.h1, commitment, bezout_b, remainder, nonce], &[bezout_a])
+    }, rng)?;
+
+    Ok(DisjointnessProof { bezout_a, bezout_b, remainder, nonce, response })
+}
+
+
+/// Verifies a proof that no element of `elements` is in the committed set
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commitzk`
+/// * `elements` - The elements, in any order
+/// * `proof` - The proof returned by `prove_disjoint`
+///
+/// # Returns
+///
+/// `Ok(())` if no element is in the set
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::MalformedProof` if the response does not have one coefficient per element
+/// * `KzgError::SchnorrFailed` if the proof of knowledge of r_hat is invalid
+/// * `KzgError::PairingCheckFailed` if the Bézout coefficients do not match the commitment
+///
+pub fn verify_disjoint<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, elements: &[T::Scalar], proof: &DisjointnessProof<T>) -> Result<(), KzgError> {
+    check_batch::<T>(pk, elements)?;
+    if proof.response.len() != elements.len() {
+        return Err(KzgError::MalformedProof);
+    }
+
+    verify_hidden_polynomial(pk, proof.remainder, proof.nonce, &proof.response, |remainder, nonce| {
+        transcript_challenge::<T>(b"kzg disjointness", elements, &[pk.h1, commitment.0, proof.bezout_b, remainder, nonce], &[proof.bezout_a])
+    })?;
+
+    let vanishing = poly::roots_polynomial::<T>(elements);
+    let vanishing_g2 = commit_poly_g2::<T>(&vanishing, &pk.g2_powers)?;
+
+    let left = T::multi_pairing(&[(commitment.0, proof.bezout_a), (proof.bezout_b, vanishing_g2)]);
+    let right = T::pairing(&T::g1add(&T::g1(), &proof.remainder), &T::g2());
+
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+    use crate::tape::{random_scalars, setup_tape};
+    use crate::{commitzk, setup};
+    use super::*;
+
+    // this tests completeness and soundness of disjointness proofs
+    #[quickcheck]
+    fn test_disjoint(size: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let degree = 8;
+        let size = (size % 6) as usize;
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(5);
+        let elements: Vec<_> = random_scalars::<Curve, _>(size);
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let mut rng = rand::rng();
+        let proof = prove_disjoint(&pk, &opening, &elements, &mut rng).expect("no element is in the set");
+        if verify_disjoint(&pk, &commitment, &elements, &proof).is_err() {
+            return false;
+        }
+
+        // an element of the set cannot be added
+        let mut larger = elements.clone();
+        larger.push(*set.iter().next().expect("set is not empty"));
+        if prove_disjoint(&pk, &opening, &larger, &mut rng).err() != Some(KzgError::ElementInSet) {
+            return false;
+        }
+
+        // nor can the proof be reused for a different list of the same size
+        let mut other = larger.clone();
+        other.remove(0);
+        size == 0 || verify_disjoint(&pk, &commitment, &other, &proof).is_err()
+    }
+
+    #[test]
+    fn test_invalid_disjointness_proofs() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(4, &mut rng);
+
+        let set: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let elements: Vec<_> = [4, 5].iter().map(Curve::scalar_from_literal).collect();
+        let proof = prove_disjoint(&pk, &opening, &elements, &mut rng).expect("no element is in the set");
+
+        let duplicates = vec![elements[0], elements[0]];
+        assert_eq!(prove_disjoint(&pk, &opening, &duplicates, &mut rng).err(), Some(KzgError::DuplicateElement));
+
+        let mut shorter = proof.clone();
+        shorter.response.pop();
+        assert_eq!(verify_disjoint(&pk, &commitment, &elements, &shorter), Err(KzgError::MalformedProof));
+
+        // the prover must know the coefficients of the remainder commitment
+        let mut forged = proof.clone();
+        forged.remainder = Curve::g1add(&forged.remainder, &Curve::g1());
+        assert_eq!(verify_disjoint(&pk, &commitment, &elements, &forged), Err(KzgError::SchnorrFailed));
+
+        let mut forged = proof.clone();
+        forged.bezout_a = Curve::g2add(&forged.bezout_a, &Curve::g2());
+        assert!(verify_disjoint(&pk, &commitment, &elements, &forged).is_err());
+
+        // the proof does not verify against another commitment
+        let (other, _) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        assert_eq!(verify_disjoint(&pk, &other, &elements, &proof), Err(KzgError::PairingCheckFailed));
+    }
+}
@@ -0,0 +1,599 @@
+//! # EIP-4844 Blob Commitments
+//!
+//! Blob commitments and proofs following the polynomial commitments of the deneb
+//! consensus specs, built on the `Curve` trait so both curve implementations can be used.
+//!
+//! A blob holds `FIELD_ELEMENTS_PER_BLOB` canonical big endian field elements, the evaluations
+//! of a polynomial over the roots of unity of that order in bit reversed order. Commitments
+//! and proofs are the 48 byte compressed points of plain (non-hiding) KZG against the
+//! Lagrange form of the Ethereum trusted setup, so they agree byte for byte with other
+//! implementations of the spec.
+//!
+//! `TrustedSetup::from_text` reads the `trusted_setup.txt` format distributed with the
+//! reference implementations: the number of G1 and G2 points followed by the hex encoded
+//! G1 points in Lagrange form and the G2 points in monomial form. Trailing G1 points in
+//! monomial form, as in newer versions of the file, are ignored.
+//! With the `serde` feature, `TrustedSetup::from_ceremony_json` reads the `transcript.json`
+//! of the Ethereum KZG ceremony itself, which holds the powers in monomial form only.
+//!
+//! ```no_run
+//! use kzg::curve::FastCurve;
+//! use kzg::eip4844::{blob_to_kzg_commitment, compute_blob_kzg_proof, verify_blob_kzg_proof, TrustedSetup, BYTES_PER_BLOB};
+//!
+//! let text = std::fs::read_to_string("trusted_setup.txt").expect("trusted setup is readable");
+//! let setup = TrustedSetup::<FastCurve>::from_text(&text).expect("trusted setup is valid");
+//!
+//! let blob = vec![0u8; BYTES_PER_BLOB];
+//! let commitment = blob_to_kzg_commitment(&setup, &blob).expect("blob is valid");
+//! let proof = compute_blob_kzg_proof(&setup, &blob, &commitment).expect("blob is valid");
+//! assert_eq!(verify_blob_kzg_proof(&setup, &blob, &commitment, &proof), Ok(true));
+//! ```
+
+use sha2::{Digest, Sha256};
+use crate::curve::{Curve, G1_BYTES, G2_BYTES, SCALAR_BYTES};
+use crate::{lagrange, poly, transcript_challenge, KzgError};
+
+/// Number of field elements in a blob
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// Number of bytes of an encoded field element
+pub const BYTES_PER_FIELD_ELEMENT: usize = SCALAR_BYTES;
+/// Number of bytes of a blob
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+
+const LOG_FIELD_ELEMENTS_PER_BLOB: u32 = 12;
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+
+
+/// The Ethereum KZG trusted setup
+///
+/// # Fields
+///
+/// * `g1_lagrange_brp` - g^L_i(τ) for the Lagrange polynomials of the roots of unity, in bit reversed order
+/// * `g2_monomial` - [g2, g2^τ, g2^(τ^2), ...]
+/// * `roots_of_unity_brp` - The roots of unity of order `FIELD_ELEMENTS_PER_BLOB`, in bit reversed order
+pub struct TrustedSetup<T: Curve> {
+    g1_lagrange_brp: Vec<T::G1>,
+    g2_monomial: Vec<T::G2>,
+    roots_of_unity_brp: Vec<T::Scalar>
+}
+
+impl<T: Curve> TrustedSetup<T> {
+    /// Creates a setup from its points
+    ///
+    /// # Arguments
+    ///
+    /// * `g1_lagrange` - The `FIELD_ELEMENTS_PER_BLOB` G1 points in Lagrange form, in natural order
+    /// * `g2_monomial` - At least two G2 points in monomial form, starting with g2
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the number of points is wrong or the first G2 point is not g2
+    ///
+    pub fn from_points(g1_lagrange: Vec<T::G1>, g2_monomial: Vec<T::G2>) -> Result<Self, KzgError> {
+        if g1_lagrange.len() != FIELD_ELEMENTS_PER_BLOB || g2_monomial.len() < 2 || g2_monomial[0] != T::g2() {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let root = poly::root_of_unity::<T>(LOG_FIELD_ELEMENTS_PER_BLOB);
+        let mut roots_of_unity = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+        let mut current = T::scalar_from_literal(&1);
+        for _ in 0..FIELD_ELEMENTS_PER_BLOB {
+            roots_of_unity.push(current);
+            current = current * root;
+        }
+
+        Ok(TrustedSetup {
+            g1_lagrange_brp: bit_reversal_permutation(&g1_lagrange),
+            g2_monomial,
+            roots_of_unity_brp: bit_reversal_permutation(&roots_of_unity)
+        })
+    }
+
+    /// Reads a setup in the `trusted_setup.txt` format
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the text is not a valid setup
+    ///
+    pub fn from_text(text: &str) -> Result<Self, KzgError> {
+        let mut tokens = text.split_whitespace();
+        let mut count = || -> Result<usize, KzgError> {
+            tokens.next().and_then(|token| token.parse().ok()).ok_or(KzgError::InvalidEncoding)
+        };
+        let g1_count = count()?;
+        let g2_count = count()?;
+        if g1_count != FIELD_ELEMENTS_PER_BLOB {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let mut g1_lagrange = Vec::with_capacity(g1_count);
+        for _ in 0..g1_count {
+            let bytes = decode_hex::<G1_BYTES>(tokens.next())?;
+            g1_lagrange.push(T::g1_from_bytes(&bytes).ok_or(KzgError::InvalidEncoding)?);
+        }
+
+        let mut g2_monomial = Vec::with_capacity(g2_count);
+        for _ in 0..g2_count {
+            let bytes = decode_hex::<G2_BYTES>(tokens.next())?;
+            g2_monomial.push(T::g2_from_bytes(&bytes).ok_or(KzgError::InvalidEncoding)?);
+        }
+
+        Self::from_points(g1_lagrange, g2_monomial)
+    }
+
+    /// Reads the setup from the `transcript.json` of the Ethereum KZG ceremony
+    ///
+    /// The transcript holds one sub-ceremony per number of G1 powers, each with the hex
+    /// encoded powers g^(τ^i) and g2^(τ^i) in monomial form. The powers of the sub-ceremony
+    /// with `FIELD_ELEMENTS_PER_BLOB` G1 powers are checked to step by the same τ and the
+    /// G1 powers are converted to Lagrange form.
+    ///
+    /// The ceremony has no second generator, so it only provides this non-hiding setup.
+    /// The hiding commitments need a `Pk` from `setup` or `Pk::from_ceremony`.
+    ///
+    /// The transcript is parsed with `serde_json`, so this needs the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// * `KzgError::InvalidEncoding` if there is no sub-ceremony of the blob size or a point is not valid
+    /// * `KzgError::PairingCheckFailed` if the powers do not step by the same τ
+    ///
+    #[cfg(feature = "serde")]
+    pub fn from_ceremony_json(json: &str) -> Result<Self, KzgError> {
+        let transcript: serde_json::Value = serde_json::from_str(json).map_err(|_| KzgError::InvalidEncoding)?;
+        let powers = transcript["transcripts"].as_array().ok_or(KzgError::InvalidEncoding)?.iter()
+            .map(|ceremony| &ceremony["powersOfTau"])
+            .find(|powers| powers["G1Powers"].as_array().is_some_and(|g1| g1.len() == FIELD_ELEMENTS_PER_BLOB))
+            .ok_or(KzgError::InvalidEncoding)?;
+        let g1_tokens = powers["G1Powers"].as_array().ok_or(KzgError::InvalidEncoding)?;
+        let g2_tokens = powers["G2Powers"].as_array().ok_or(KzgError::InvalidEncoding)?;
+
+        let g1_monomial = g1_tokens.iter().map(|token| {
+            T::g1_from_bytes(&decode_hex::<G1_BYTES>(token.as_str())?).ok_or(KzgError::InvalidEncoding)
+        }).collect::<Result<Vec<_>, _>>()?;
+        let g2_monomial = g2_tokens.iter().map(|token| {
+            T::g2_from_bytes(&decode_hex::<G2_BYTES>(token.as_str())?).ok_or(KzgError::InvalidEncoding)
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        check_powers::<T>(&g1_monomial, &g2_monomial)?;
+        Self::from_points(poly::lagrange_g1::<T>(&g1_monomial), g2_monomial)
+    }
+}
+
+
+/// Computes the commitment to a blob
+///
+/// # Errors
+///
+/// `KzgError::InvalidEncoding` if the blob does not have `BYTES_PER_BLOB` bytes of canonical field elements
+///
+pub fn blob_to_kzg_commitment<T: Curve>(setup: &TrustedSetup<T>, blob: &[u8]) -> Result<[u8; G1_BYTES], KzgError> {
+    let polynomial = blob_to_polynomial::<T>(blob)?;
+    Ok(T::g1_to_bytes(&T::msm_g1(&polynomial, &setup.g1_lagrange_brp)))
+}
+
+
+/// Computes the proof for the evaluation of a blob at z
+///
+/// # Returns
+///
+/// The proof and the evaluation y
+///
+/// # Errors
+///
+/// `KzgError::InvalidEncoding` if the blob or z are not canonical field elements
+///
+pub fn compute_kzg_proof<T: Curve>(setup: &TrustedSetup<T>, blob: &[u8], z: &[u8; SCALAR_BYTES]) -> Result<([u8; G1_BYTES], [u8; SCALAR_BYTES]), KzgError> {
+    let polynomial = blob_to_polynomial::<T>(blob)?;
+    let (proof, y) = compute_kzg_proof_impl(setup, &polynomial, bytes_to_bls_field::<T>(z)?);
+    Ok((T::g1_to_bytes(&proof), T::scalar_to_bytes(&y)))
+}
+
+
+/// Computes the proof for a blob at the Fiat-Shamir challenge of the blob and its commitment
+///
+/// # Errors
+///
+/// `KzgError::InvalidEncoding` if the blob or the commitment are not valid encodings
+///
+pub fn compute_blob_kzg_proof<T: Curve>(setup: &TrustedSetup<T>, blob: &[u8], commitment: &[u8; G1_BYTES]) -> Result<[u8; G1_BYTES], KzgError> {
+    bytes_to_g1::<T>(commitment)?;
+    let polynomial = blob_to_polynomial::<T>(blob)?;
+
+    let challenge = compute_challenge::<T>(blob, commitment);
+    let (proof, _) = compute_kzg_proof_impl(setup, &polynomial, challenge);
+    Ok(T::g1_to_bytes(&proof))
+}
+
+
+/// Verifies that the committed polynomial evaluates to y at z
+///
+/// # Returns
+///
+/// `Ok(true)` if the proof is valid and `Ok(false)` if it is not
+///
+/// # Errors
+///
+/// `KzgError::InvalidEncoding` if one of the inputs is not a valid encoding
+///
+pub fn verify_kzg_proof<T: Curve>(setup: &TrustedSetup<T>, commitment: &[u8; G1_BYTES], z: &[u8; SCALAR_BYTES], y: &[u8; SCALAR_BYTES], proof: &[u8; G1_BYTES]) -> Result<bool, KzgError> {
+    Ok(verify_kzg_proof_impl(
+        setup,
+        bytes_to_g1::<T>(commitment)?,
+        bytes_to_bls_field::<T>(z)?,
+        bytes_to_bls_field::<T>(y)?,
+        bytes_to_g1::<T>(proof)?
+    ))
+}
+
+
+/// Verifies a proof created by `compute_blob_kzg_proof`
+///
+/// # Returns
+///
+/// `Ok(true)` if the proof is valid and `Ok(false)` if it is not
+///
+/// # Errors
+///
+/// `KzgError::InvalidEncoding` if one of the inputs is not a valid encoding
+///
+pub fn verify_blob_kzg_proof<T: Curve>(setup: &TrustedSetup<T>, blob: &[u8], commitment: &[u8; G1_BYTES], proof: &[u8; G1_BYTES]) -> Result<bool, KzgError> {
+    let commitment_point = bytes_to_g1::<T>(commitment)?;
+    let proof = bytes_to_g1::<T>(proof)?;
+    let polynomial = blob_to_polynomial::<T>(blob)?;
+
+    let challenge = compute_challenge::<T>(blob, commitment);
+    let y = evaluate_polynomial_in_evaluation_form(setup, &polynomial, challenge);
+    Ok(verify_kzg_proof_impl(setup, commitment_point, challenge, y, proof))
+}
+
+
+/// Verifies many proofs created by `compute_blob_kzg_proof` with a single multi-pairing
+///
+/// # Returns
+///
+/// `Ok(true)` if every proof is valid and `Ok(false)` otherwise
+///
+/// # Errors
+///
+/// * `KzgError::MalformedProof` if the numbers of blobs, commitments and proofs differ
+/// * `KzgError::InvalidEncoding` if one of the inputs is not a valid encoding
+///
+pub fn verify_blob_kzg_proof_batch<T: Curve, B: AsRef<[u8]>>(setup: &TrustedSetup<T>, blobs: &[B], commitments: &[[u8; G1_BYTES]], proofs: &[[u8; G1_BYTES]]) -> Result<bool, KzgError> {
+    if blobs.len() != commitments.len() || blobs.len() != proofs.len() {
+        return Err(KzgError::MalformedProof);
+    }
+
+    let mut commitment_points = Vec::with_capacity(blobs.len());
+    let mut proof_points = Vec::with_capacity(blobs.len());
+    let mut zs = Vec::with_capacity(blobs.len());
+    let mut ys = Vec::with_capacity(blobs.len());
+    for ((blob, commitment), proof) in blobs.iter().zip(commitments).zip(proofs) {
+        let blob = blob.as_ref();
+        commitment_points.push(bytes_to_g1::<T>(commitment)?);
+        proof_points.push(bytes_to_g1::<T>(proof)?);
+
+        let polynomial = blob_to_polynomial::<T>(blob)?;
+        let challenge = compute_challenge::<T>(blob, commitment);
+        ys.push(evaluate_polynomial_in_evaluation_form(setup, &polynomial, challenge));
+        zs.push(challenge);
+    }
+
+    Ok(verify_kzg_proof_batch(setup, &commitment_points, &zs, &ys, &proof_points))
+}
+
+
+fn blob_to_polynomial<T: Curve>(blob: &[u8]) -> Result<Vec<T::Scalar>, KzgError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(KzgError::InvalidEncoding);
+    }
+
+    blob.chunks(BYTES_PER_FIELD_ELEMENT).map(|chunk| {
+        bytes_to_bls_field::<T>(chunk.try_into().expect("chunks have the length of a field element"))
+    }).collect()
+}
+
+fn bytes_to_bls_field<T: Curve>(bytes: &[u8; SCALAR_BYTES]) -> Result<T::Scalar, KzgError> {
+    T::scalar_from_bytes(bytes).ok_or(KzgError::InvalidEncoding)
+}
+
+// accepts the point at infinity and points in the prime order subgroup
+fn bytes_to_g1<T: Curve>(bytes: &[u8; G1_BYTES]) -> Result<T::G1, KzgError> {
+    T::g1_from_bytes(bytes).ok_or(KzgError::InvalidEncoding)
+}
+
+// sha256 interpreted as a big endian integer modulo the group order
+fn hash_to_bls_field<T: Curve>(data: &[u8]) -> T::Scalar {
+    let mut bytes = [0u8; 64];
+    bytes[32..].copy_from_slice(&Sha256::digest(data));
+    T::scalar_from_bytes_wide(&bytes)
+}
+
+fn compute_challenge<T: Curve>(blob: &[u8], commitment: &[u8; G1_BYTES]) -> T::Scalar {
+    let mut data = Vec::with_capacity(FIAT_SHAMIR_PROTOCOL_DOMAIN.len() + 16 + BYTES_PER_BLOB + G1_BYTES);
+    data.extend_from_slice(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    data.extend_from_slice(blob);
+    data.extend_from_slice(commitment);
+    hash_to_bls_field::<T>(&data)
+}
+
+// the polynomial is in evaluation form over the roots of unity in bit reversed order
+fn evaluate_polynomial_in_evaluation_form<T: Curve>(setup: &TrustedSetup<T>, polynomial: &[T::Scalar], z: T::Scalar) -> T::Scalar {
+    lagrange::barycentric::<T>(&setup.roots_of_unity_brp, polynomial, z)
+}
+
+// the quotient (p(x) - y) / (x - z) in evaluation form committed against the setup
+fn compute_kzg_proof_impl<T: Curve>(setup: &TrustedSetup<T>, polynomial: &[T::Scalar], z: T::Scalar) -> (T::G1, T::Scalar) {
+    let y = evaluate_polynomial_in_evaluation_form(setup, polynomial, z);
+    let quotient = lagrange::quotient::<T>(&setup.roots_of_unity_brp, polynomial, z, y);
+    (T::msm_g1(&quotient, &setup.g1_lagrange_brp), y)
+}
+
+// e(C · g^-y, -g2) · e(π, g2^(τ - z)) = 1
+fn verify_kzg_proof_impl<T: Curve>(setup: &TrustedSetup<T>, commitment: T::G1, z: T::Scalar, y: T::Scalar, proof: T::G1) -> bool {
+    let x_minus_z = T::g2sub(&setup.g2_monomial[1], &T::g2mul(&z, &T::g2()));
+    let p_minus_y = T::g1sub(&commitment, &T::g1mul(&y, &T::g1()));
+
+    T::multi_pairing(&[(p_minus_y, g2_neg::<T>(&T::g2())), (proof, x_minus_z)]) == T::gt_one()
+}
+
+// combines the proofs with powers of a random r, as in the spec
+fn verify_kzg_proof_batch<T: Curve>(setup: &TrustedSetup<T>, commitments: &[T::G1], zs: &[T::Scalar], ys: &[T::Scalar], proofs: &[T::G1]) -> bool {
+    let n = commitments.len();
+
+    let mut data = Vec::with_capacity(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.len() + 16 + n * (2 * G1_BYTES + 2 * SCALAR_BYTES));
+    data.extend_from_slice(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    data.extend_from_slice(&(n as u64).to_be_bytes());
+    for i in 0..n {
+        data.extend_from_slice(&T::g1_to_bytes(&commitments[i]));
+        data.extend_from_slice(&T::scalar_to_bytes(&zs[i]));
+        data.extend_from_slice(&T::scalar_to_bytes(&ys[i]));
+        data.extend_from_slice(&T::g1_to_bytes(&proofs[i]));
+    }
+    let r = hash_to_bls_field::<T>(&data);
+
+    let mut r_powers = Vec::with_capacity(n);
+    let mut current = T::scalar_from_literal(&1);
+    for _ in 0..n {
+        r_powers.push(current);
+        current = current * r;
+    }
+
+    let proof_lincomb = T::msm_g1(&r_powers, proofs);
+    let z_r_powers: Vec<_> = zs.iter().zip(r_powers.iter()).map(|(z, r)| *z * *r).collect();
+    let proof_z_lincomb = T::msm_g1(&z_r_powers, proofs);
+    let c_minus_ys: Vec<_> = commitments.iter().zip(ys).map(|(c, y)| T::g1sub(c, &T::g1mul(y, &T::g1()))).collect();
+    let c_minus_y_lincomb = T::msm_g1(&r_powers, &c_minus_ys);
+
+    let pairs = [
+        (proof_lincomb, g2_neg::<T>(&setup.g2_monomial[1])),
+        (T::g1add(&c_minus_y_lincomb, &proof_z_lincomb), T::g2())
+    ];
+    T::multi_pairing(&pairs) == T::gt_one()
+}
+
+fn g2_neg<T: Curve>(x: &T::G2) -> T::G2 {
+    T::g2sub(&T::g2mul(&T::scalar_from_literal(&0), &T::g2()), x)
+}
+
+fn bit_reversal_permutation<V: Copy>(values: &[V]) -> Vec<V> {
+    let bits = values.len().trailing_zeros();
+    (0..values.len()).map(|i| values[poly::reverse_bits(i, bits)]).collect()
+}
+
+// checks that g^(τ^i) and g2^(τ^i) start at the generators and step by the same τ, for a
+// random linear combination of the steps with coefficients hashed from the powers
+fn check_powers<T: Curve>(g1_monomial: &[T::G1], g2_monomial: &[T::G2]) -> Result<(), KzgError> {
+    let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+    if g1_monomial.len() < 2 || g2_monomial.len() < 2 || g1_monomial[0] != T::g1() || g2_monomial[0] != T::g2() {
+        return Err(KzgError::InvalidEncoding);
+    }
+    if g1_monomial.contains(&identity) {
+        return Err(KzgError::InvalidEncoding);
+    }
+
+    let (n, m) = (g1_monomial.len() - 1, g2_monomial.len() - 1);
+    let rho = transcript_challenge::<T>(b"kzg ethereum ceremony", &[], g1_monomial, g2_monomial);
+    let mut r = Vec::with_capacity(n.max(m));
+    let mut power = T::scalar_from_literal(&1);
+    for _ in 0..n.max(m) {
+        r.push(power);
+        power = power * rho;
+    }
+
+    // e(Σ r_i g^(τ^(i+1)), g2) = e(Σ r_i g^(τ^i), g2^τ)
+    let upper = T::msm_g1(&r[..n], &g1_monomial[1..]);
+    let lower = T::msm_g1(&r[..n], &g1_monomial[..n]);
+    if T::multi_pairing(&[(upper, T::g2()), (T::g1sub(&identity, &lower), g2_monomial[1])]) != T::gt_one() {
+        return Err(KzgError::PairingCheckFailed);
+    }
+
+    // e(g, Σ r_i g2^(τ^(i+1))) = e(g^τ, Σ r_i g2^(τ^i))
+    let upper = T::msm_g2(&r[..m], &g2_monomial[1..]);
+    let lower = T::msm_g2(&r[..m], &g2_monomial[..m]);
+    if T::multi_pairing(&[(T::g1(), upper), (T::g1sub(&identity, &g1_monomial[1]), lower)]) != T::gt_one() {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+// hex with an optional 0x prefix
+fn decode_hex<const N: usize>(token: Option<&str>) -> Result<[u8; N], KzgError> {
+    let token = token.ok_or(KzgError::InvalidEncoding)?;
+    let digits = token.strip_prefix("0x").unwrap_or(token).as_bytes();
+    if digits.len() != 2 * N {
+        return Err(KzgError::InvalidEncoding);
+    }
+
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| KzgError::InvalidEncoding)?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| KzgError::InvalidEncoding)?;
+    }
+    Ok(bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+    use crate::curve::FastCurve;
+    use super::*;
+
+    // a setup for a known τ, the Lagrange points are L_i(τ) = ω^i (τ^n - 1) / (n (τ - ω^i))
+    fn test_setup() -> &'static TrustedSetup<FastCurve> {
+        static SETUP: OnceLock<TrustedSetup<FastCurve>> = OnceLock::new();
+        SETUP.get_or_init(|| {
+            let tau = FastCurve::scalar_from_literal(&0x5eed);
+            let n = FIELD_ELEMENTS_PER_BLOB as u128;
+            let one = FastCurve::scalar_from_literal(&1);
+            let factor = (FastCurve::scalar_pow(&tau, &n) - one) * FastCurve::scalar_inv(&FastCurve::scalar_from_literal(&n));
+
+            let root = poly::root_of_unity::<FastCurve>(LOG_FIELD_ELEMENTS_PER_BLOB);
+            let mut domain = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+            let mut omega = one;
+            for _ in 0..FIELD_ELEMENTS_PER_BLOB {
+                domain.push(omega);
+                omega = omega * root;
+            }
+
+            let mut inverses: Vec<_> = domain.iter().map(|omega| tau - *omega).collect();
+            poly::batch_inverse::<FastCurve>(&mut inverses);
+            let g1_lagrange = domain.iter().zip(inverses).map(|(omega, inverse)| {
+                FastCurve::g1mul(&(*omega * factor * inverse), &FastCurve::g1())
+            }).collect();
+            let g2_monomial = vec![FastCurve::g2(), FastCurve::g2mul(&tau, &FastCurve::g2())];
+
+            TrustedSetup::from_points(g1_lagrange, g2_monomial).expect("setup has the right size")
+        })
+    }
+
+    fn random_blob() -> Vec<u8> {
+        (0..FIELD_ELEMENTS_PER_BLOB)
+            .flat_map(|_| FastCurve::scalar_to_bytes(&FastCurve::scalar_from_literal(&rand::random())))
+            .collect()
+    }
+
+    #[test]
+    fn test_kzg_proof() {
+        let setup = test_setup();
+        let blob = random_blob();
+        let commitment = blob_to_kzg_commitment(setup, &blob).expect("blob is valid");
+
+        // outside of the domain
+        let z = FastCurve::scalar_to_bytes(&FastCurve::scalar_from_literal(&rand::random()));
+        let (proof, y) = compute_kzg_proof(setup, &blob, &z).expect("blob is valid");
+        assert_eq!(verify_kzg_proof(setup, &commitment, &z, &y, &proof), Ok(true));
+        assert_eq!(verify_kzg_proof(setup, &commitment, &z, &z, &proof), Ok(false));
+
+        // on a root of unity the evaluation is the blob element
+        let z = FastCurve::scalar_to_bytes(&setup.roots_of_unity_brp[7]);
+        let (proof, y) = compute_kzg_proof(setup, &blob, &z).expect("blob is valid");
+        assert_eq!(&y[..], &blob[7 * BYTES_PER_FIELD_ELEMENT..8 * BYTES_PER_FIELD_ELEMENT]);
+        assert_eq!(verify_kzg_proof(setup, &commitment, &z, &y, &proof), Ok(true));
+    }
+
+    #[test]
+    fn test_blob_kzg_proof_batch() {
+        let setup = test_setup();
+        let blobs: Vec<_> = (0..3).map(|_| random_blob()).collect();
+        let commitments: Vec<_> = blobs.iter().map(|blob| blob_to_kzg_commitment(setup, blob).expect("blob is valid")).collect();
+        let proofs: Vec<_> = blobs.iter().zip(commitments.iter())
+            .map(|(blob, commitment)| compute_blob_kzg_proof(setup, blob, commitment).expect("blob is valid"))
+            .collect();
+
+        assert_eq!(verify_blob_kzg_proof(setup, &blobs[0], &commitments[0], &proofs[0]), Ok(true));
+        assert_eq!(verify_blob_kzg_proof(setup, &blobs[0], &commitments[0], &proofs[1]), Ok(false));
+
+        assert_eq!(verify_blob_kzg_proof_batch(setup, &blobs, &commitments, &proofs), Ok(true));
+        assert_eq!(verify_blob_kzg_proof_batch::<_, Vec<u8>>(setup, &[], &[], &[]), Ok(true));
+
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert_eq!(verify_blob_kzg_proof_batch(setup, &blobs, &commitments, &swapped), Ok(false));
+        assert_eq!(verify_blob_kzg_proof_batch(setup, &blobs, &commitments, &proofs[1..]), Err(KzgError::MalformedProof));
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let setup = test_setup();
+
+        // a field element that is not reduced
+        let mut blob = random_blob();
+        blob[..BYTES_PER_FIELD_ELEMENT].fill(0xff);
+        assert_eq!(blob_to_kzg_commitment(setup, &blob), Err(KzgError::InvalidEncoding));
+        assert_eq!(blob_to_kzg_commitment(setup, &blob[1..]), Err(KzgError::InvalidEncoding));
+
+        // a point that is not on the curve
+        let commitment = [0x80; G1_BYTES];
+        assert_eq!(compute_blob_kzg_proof(setup, &random_blob(), &commitment), Err(KzgError::InvalidEncoding));
+    }
+
+    // the text format round trips
+    #[test]
+    fn test_from_text() {
+        let setup = test_setup();
+
+        let mut text = format!("{}\n{}\n", FIELD_ELEMENTS_PER_BLOB, setup.g2_monomial.len());
+        for point in bit_reversal_permutation(&setup.g1_lagrange_brp) {
+            text.extend(FastCurve::g1_to_bytes(&point).iter().map(|byte| format!("{:02x}", byte)));
+            text.push('\n');
+        }
+        for point in setup.g2_monomial.iter() {
+            text.extend(FastCurve::g2_to_bytes(point).iter().map(|byte| format!("{:02x}", byte)));
+            text.push('\n');
+        }
+
+        let parsed = TrustedSetup::<FastCurve>::from_text(&text).expect("setup is valid");
+        assert_eq!(parsed.g1_lagrange_brp, setup.g1_lagrange_brp);
+        assert_eq!(parsed.g2_monomial, setup.g2_monomial);
+
+        assert!(TrustedSetup::<FastCurve>::from_text("4096\n65\n").is_err());
+    }
+
+    // a transcript in the format of the Ethereum KZG ceremony for the τ of `test_setup`
+    #[cfg(feature = "serde")]
+    fn ceremony_json(g1_powers: &[usize]) -> String {
+        let tau = FastCurve::scalar_from_literal(&0x5eed);
+        let hex = |bytes: &[u8]| format!("\"0x{}\"", bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>());
+
+        let transcripts: Vec<_> = g1_powers.iter().map(|count| {
+            let mut g1 = Vec::with_capacity(*count);
+            let mut point = FastCurve::g1();
+            for _ in 0..*count {
+                g1.push(hex(&FastCurve::g1_to_bytes(&point)));
+                point = FastCurve::g1mul(&tau, &point);
+            }
+            let g2 = [hex(&FastCurve::g2_to_bytes(&FastCurve::g2())), hex(&FastCurve::g2_to_bytes(&FastCurve::g2mul(&tau, &FastCurve::g2())))];
+            format!(
+                "{{\"numG1Powers\": {}, \"numG2Powers\": 2, \"powersOfTau\": {{\"G1Powers\": [{}], \"G2Powers\": [{}]}}, \"witness\": {{\"potPubkeys\": []}}}}",
+                count, g1.join(", "), g2.join(", ")
+            )
+        }).collect();
+        format!("{{\"transcripts\": [{}], \"participantIds\": []}}", transcripts.join(", "))
+    }
+
+    // the ceremony transcript gives the same setup as the Lagrange points of the same τ
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_ceremony_json() {
+        let json = ceremony_json(&[8, FIELD_ELEMENTS_PER_BLOB]);
+        let parsed = TrustedSetup::<FastCurve>::from_ceremony_json(&json).expect("transcript is valid");
+        assert_eq!(parsed.g1_lagrange_brp, test_setup().g1_lagrange_brp);
+        assert_eq!(parsed.g2_monomial, test_setup().g2_monomial);
+
+        assert_eq!(TrustedSetup::<FastCurve>::from_ceremony_json(&ceremony_json(&[8])).err(), Some(KzgError::InvalidEncoding));
+
+        // swapping two powers breaks the steps
+        let generator = format!("0x{}", FastCurve::g1_to_bytes(&FastCurve::g1()).iter().map(|byte| format!("{:02x}", byte)).collect::<String>());
+        let second = json.rfind(&generator).expect("the generator is the first power");
+        let (head, tail) = json.split_at(second);
+        let mut powers: Vec<_> = tail.splitn(4, ", ").map(str::to_string).collect();
+        powers.swap(1, 2);
+        let forged = format!("{}{}", head, powers.join(", "));
+        assert_eq!(TrustedSetup::<FastCurve>::from_ceremony_json(&forged).err(), Some(KzgError::PairingCheckFailed));
+    }
+}
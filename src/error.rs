@@ -0,0 +1,55 @@
+//! # Errors
+//!
+//! The error type returned by the protocol functions.
+
+use std::fmt;
+
+/// Errors returned by the protocol functions
+///
+/// # Variants
+///
+/// * `SetTooLarge` - The set or polynomial has more elements than the public key supports
+/// * `MalformedOpening` - The opening is not of the form returned by `commitzk`
+/// * `MalformedProof` - The proof does not have the expected shape
+/// * `SchnorrFailed` - The Schnorr proof of a non-membership proof is invalid
+/// * `PairingCheckFailed` - The pairing equation does not hold
+/// * `ClaimedMembershipIsFalse` - A non-membership proof shows that φ(kj) = 0, the element is in the set
+/// * `ElementNotInSet` - A membership proof was requested for an element that is not in the set
+/// * `ElementInSet` - A non-membership proof was requested for an element of the set
+/// * `DuplicateElement` - An element was queried more than once in a batch
+/// * `WrongMultiplicity` - A multiplicity proof was requested for a multiplicity the element does not have
+/// * `InvalidEncoding` - Bytes could not be decoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KzgError {
+    SetTooLarge { max: usize, got: usize },
+    MalformedOpening,
+    MalformedProof,
+    SchnorrFailed,
+    PairingCheckFailed,
+    ClaimedMembershipIsFalse,
+    ElementNotInSet,
+    ElementInSet,
+    DuplicateElement,
+    WrongMultiplicity,
+    InvalidEncoding
+}
+
+impl fmt::Display for KzgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KzgError::SetTooLarge { max, got } => write!(f, "set of size {got} exceeds the maximum size {max} of the public key"),
+            KzgError::MalformedOpening => write!(f, "malformed opening"),
+            KzgError::MalformedProof => write!(f, "malformed proof"),
+            KzgError::SchnorrFailed => write!(f, "schnorr proof verification failed"),
+            KzgError::PairingCheckFailed => write!(f, "pairing check failed"),
+            KzgError::ClaimedMembershipIsFalse => write!(f, "non-membership proof for an element of the set"),
+            KzgError::ElementNotInSet => write!(f, "element is not in the set"),
+            KzgError::ElementInSet => write!(f, "element is in the set"),
+            KzgError::DuplicateElement => write!(f, "element appears more than once in the batch"),
+            KzgError::WrongMultiplicity => write!(f, "element does not have the claimed multiplicity"),
+            KzgError::InvalidEncoding => write!(f, "invalid encoding")
+        }
+    }
+}
+
+impl std::error::Error for KzgError {}
@@ -0,0 +1,460 @@
+//! # Set Intersection Cardinality
+//!
+//! Proofs of the size of the intersection of two committed sets, without revealing either set.
+//!
+//! The prover holds both openings, either because one party committed to both sets or
+//! because the owners handed their openings to a party they both trust.
+//! With I = gcd(φ_A, φ_B) of degree k, the cofactors P_A = φ_A / I and P_B = φ_B / I
+//! and their Bézout coefficients a·P_A + b·P_B = 1, the prover shows that
+//! * φ_A = I·P_A and φ_B = I·P_B, so I divides both set polynomials
+//! * a·P_A + b·P_B = 1, so the cofactors are coprime and I is the gcd
+//! * I - x^k has degree below k, so I is monic of degree k
+//!
+//! Every polynomial is committed with hiding like `commitzk`. The identities are checked at a
+//! random point z: every polynomial is opened to a Pedersen commitment g^f(z) · h^f_hat(z)
+//! with a single aggregated witness, and the products between the hidden evaluations are shown
+//! with Schnorr proofs. The degree of I is shown with the commitment to (I - x^k)·x^(d + 1 - k),
+//! which only fits the public key if the degree is below k.
+//!
+//! The proof contains a hiding commitment to the intersection polynomial, and the prover
+//! gets its opening to answer `queryzk` queries about the intersection.
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use kzg::{setup, commitzk, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::intersection::{prove_intersection, verify_intersection};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let a: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+//! let b: HashSet<_> = [2, 3, 4].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (commitment_a, opening_a) = commitzk(&pk, &a, &mut rng).expect("set fits the public key");
+//! let (commitment_b, opening_b) = commitzk(&pk, &b, &mut rng).expect("set fits the public key");
+//!
+//! let (_, proof) = prove_intersection(&pk, &opening_a, &opening_b, &mut rng).expect("openings fit the public key");
+//! assert!(verify_intersection(&pk, &commitment_a, &commitment_b, &proof).is_ok());
+//! assert_eq!(proof.cardinality, 2);
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{check_opening, commit_hiding, commit_pair, create_witness, poly, transcript_challenge, Commitment, KzgError, Opening, Pk};
+
+// positions of the polynomials in `IntersectionProof::evaluations`
+const PHI_A: usize = 0;
+const PHI_B: usize = 1;
+const INTERSECTION: usize = 2;
+const COFACTOR_A: usize = 3;
+const COFACTOR_B: usize = 4;
+const BEZOUT_A: usize = 5;
+const BEZOUT_B: usize = 6;
+const POLYNOMIALS: usize = 7;
+
+
+/// Proof that the committed value of Z is the product of the committed values of X and Y
+///
+/// For Pedersen commitments X = g^x · h^r_x, Y = g^y · h^r_y and Z = g^z · h^r_z it shows
+/// knowledge of y, r_y and t = r_z - y · r_x with Y = g^y · h^r_y and Z = X^y · h^t.
+///
+/// # Fields
+///
+/// * `nonces` - The Schnorr nonces g^k1 · h^k2 and X^k1 · h^k3
+/// * `responses` - The Schnorr responses k1 - c · y, k2 - c · r_y and k3 - c · t
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProductProof<T: Curve> {
+    pub nonces: [T::G1; 2],
+    pub responses: [T::Scalar; 3]
+}
+
+
+/// Proof of the size of the intersection of two committed sets
+///
+/// # Fields
+///
+/// * `cardinality` - The size k of the intersection
+/// * `intersection` - The hiding commitment to the intersection polynomial I
+/// * `degree` - The commitment to (I - x^k)·x^(d + 1 - k) and its hiding polynomial shifted alike
+/// * `cofactors` - The hiding commitments to P_A and P_B
+/// * `bezout` - The hiding commitments to the Bézout coefficients a and b
+/// * `evaluations` - g^f(z) · h^f_hat(z) for φ_A, φ_B, I, P_A, P_B, a and b
+/// * `witness` - The witness for all evaluations, aggregated with powers of a random γ
+/// * `products` - Pedersen commitments to a(z)·P_A(z) and b(z)·P_B(z)
+/// * `product_proofs` - Proofs for φ_A = I·P_A, φ_B = I·P_B and the two `products`
+/// * `nonce` - The Schnorr nonce h^k for the sum of the `products`
+/// * `response` - The Schnorr response showing that the `products` add up to 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntersectionProof<T: Curve> {
+    pub cardinality: usize,
+    pub intersection: Commitment<T>,
+    pub degree: T::G1,
+    pub cofactors: [T::G1; 2],
+    pub bezout: [T::G1; 2],
+    pub evaluations: [T::G1; POLYNOMIALS],
+    pub witness: T::G1,
+    pub products: [T::G1; 2],
+    pub product_proofs: [ProductProof<T>; 4],
+    pub nonce: T::G1,
+    pub response: T::Scalar
+}
+
+
+/// Generates a proof of the size of the intersection of two committed sets
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening_a` - The opening of the first set returned by `commitzk`
+/// * `opening_b` - The opening of the second set returned by `commitzk`
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// The opening of the commitment to the intersection polynomial and the `IntersectionProof<T>`
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat of an opening are empty or differ in length,
+///   or φ is not monic
+/// * `KzgError::SetTooLarge` if an opening does not fit the public key
+///
+pub fn prove_intersection<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening_a: &Opening<T>, opening_b: &Opening<T>, rng: &mut R) -> Result<(Opening<T>, IntersectionProof<T>), KzgError> {
+    let one = T::scalar_from_literal(&1);
+    for opening in [opening_a, opening_b] {
+        check_opening(opening)?;
+        // set polynomials are monic, a decoded opening may have a zero leading coefficient
+        if opening.phi[0] != one {
+            return Err(KzgError::MalformedOpening);
+        }
+        if opening.phi.len() > pk.g_powers.len() {
+            return Err(KzgError::SetTooLarge { max: pk.g_powers.len() - 1, got: opening.phi.len() - 1 });
+        }
+    }
+
+    let zero = T::scalar_from_literal(&0);
+    let identity = T::g1mul(&zero, &T::g1());
+
+    let (intersection, _, _) = poly::extended_gcd::<T>(&opening_a.phi, &opening_b.phi);
+    let cardinality = intersection.len() - 1;
+    let (cofactor_a, _) = poly::divide::<T>(&opening_a.phi, &intersection);
+    let (cofactor_b, _) = poly::divide::<T>(&opening_b.phi, &intersection);
+    // the cofactors of the gcd of monic polynomials are coprime
+    let (bezout_a, bezout_b) = poly::bezout::<T>(&cofactor_a, &cofactor_b).ok_or(KzgError::MalformedOpening)?;
+
+    // the hiding polynomial of I has degree k - 1, so that I - x^k has degree k - 1 as well
+    let mut intersection_hat = vec![zero];
+    intersection_hat.extend((0..cardinality).map(|_| T::random_scalar(rng)));
+    let intersection = Opening { phi: intersection, phi_hat: intersection_hat };
+    let intersection_commitment = commit_pair::<T>(pk, &intersection.phi, &intersection.phi_hat)?;
+
+    let degree = if cardinality == 0 {
+        identity
+    } else {
+        let shift = pk.g_powers.len() - cardinality;
+        let mut lower = intersection.phi[1..].to_vec();
+        let mut lower_hat = intersection.phi_hat[1..].to_vec();
+        lower.resize(lower.len() + shift, zero);
+        lower_hat.resize(lower_hat.len() + shift, zero);
+        commit_pair::<T>(pk, &lower, &lower_hat)?
+    };
+
+    // the Bézout coefficients may be the zero polynomial
+    let nonzero = |f: Vec<T::Scalar>| if f.is_empty() { vec![zero] } else { f };
+    let (cofactor_a_commitment, cofactor_a) = commit_hiding(pk, cofactor_a, rng)?;
+    let (cofactor_b_commitment, cofactor_b) = commit_hiding(pk, cofactor_b, rng)?;
+    let (bezout_a_commitment, bezout_a) = commit_hiding(pk, nonzero(bezout_a), rng)?;
+    let (bezout_b_commitment, bezout_b) = commit_hiding(pk, nonzero(bezout_b), rng)?;
+
+    let openings = [opening_a, opening_b, &intersection, &cofactor_a, &cofactor_b, &bezout_a, &bezout_b];
+    let commitment_a = commit_pair::<T>(pk, &opening_a.phi, &opening_a.phi_hat)?;
+    let commitment_b = commit_pair::<T>(pk, &opening_b.phi, &opening_b.phi_hat)?;
+    let commitments = [
+        commitment_a, commitment_b, intersection_commitment,
+        cofactor_a_commitment.0, cofactor_b_commitment.0, bezout_a_commitment.0, bezout_b_commitment.0
+    ];
+
+    // the challenges are bound to the claimed cardinality
+    let bound = [T::scalar_from_literal(&(cardinality as u128))];
+    let mut transcript = commitments.to_vec();
+    transcript.push(degree);
+    let z = transcript_challenge::<T>(b"kzg intersection point", &bound, &transcript, &[]);
+
+    // hidden evaluations f(z) with their blinding f_hat(z)
+    let mut values = [zero; POLYNOMIALS];
+    let mut blindings = [zero; POLYNOMIALS];
+    let mut evaluations = [identity; POLYNOMIALS];
+    let mut witnesses = [identity; POLYNOMIALS];
+    for (i, opening) in openings.iter().enumerate() {
+        let (_, value, blinding, witness) = create_witness(&opening.phi, &opening.phi_hat, z, pk)?;
+        values[i] = value;
+        blindings[i] = blinding;
+        evaluations[i] = pedersen(pk, value, blinding);
+        witnesses[i] = witness;
+    }
+
+    transcript.extend_from_slice(&evaluations);
+    let gamma = transcript_challenge::<T>(b"kzg intersection evaluations", &bound, &transcript, &[]);
+    let witness = T::msm_g1(&powers::<T>(gamma, POLYNOMIALS), &witnesses);
+
+    // a(z)·P_A(z) and b(z)·P_B(z) hidden with fresh randomness
+    let product_blindings = [T::random_scalar(rng), T::random_scalar(rng)];
+    let product_values = [values[BEZOUT_A] * values[COFACTOR_A], values[BEZOUT_B] * values[COFACTOR_B]];
+    let products = [
+        pedersen(pk, product_values[0], product_blindings[0]),
+        pedersen(pk, product_values[1], product_blindings[1])
+    ];
+
+    let hidden = |i: usize| (evaluations[i], values[i], blindings[i]);
+    let statements = [
+        (hidden(INTERSECTION), hidden(COFACTOR_A), blindings[PHI_A]),
+        (hidden(INTERSECTION), hidden(COFACTOR_B), blindings[PHI_B]),
+        (hidden(BEZOUT_A), hidden(COFACTOR_A), product_blindings[0]),
+        (hidden(BEZOUT_B), hidden(COFACTOR_B), product_blindings[1])
+    ];
+
+    let mut product_nonces = Vec::with_capacity(statements.len());
+    let mut product_randomness = Vec::with_capacity(statements.len());
+    for ((x, _, _), _, _) in statements {
+        let k = [T::random_scalar(rng), T::random_scalar(rng), T::random_scalar(rng)];
+        product_nonces.push([pedersen(pk, k[0], k[1]), T::g1add(&T::g1mul(&k[0], &x), &T::g1mul(&k[2], &pk.h1))]);
+        product_randomness.push(k);
+    }
+
+    let k = T::random_scalar(rng);
+    let nonce = T::g1mul(&k, &pk.h1);
+
+    transcript.push(witness);
+    transcript.extend_from_slice(&products);
+    transcript.extend(product_nonces.iter().flatten());
+    transcript.push(nonce);
+    let c = transcript_challenge::<T>(b"kzg intersection products", &bound, &transcript, &[]);
+
+    let product_proofs = std::array::from_fn(|i| {
+        let ((_, _, x_blinding), (_, y_value, y_blinding), z_blinding) = statements[i];
+        let k = product_randomness[i];
+        let t = z_blinding - y_value * x_blinding;
+        ProductProof {
+            nonces: product_nonces[i],
+            responses: [k[0] - c * y_value, k[1] - c * y_blinding, k[2] - c * t]
+        }
+    });
+
+    let response = k - c * (product_blindings[0] + product_blindings[1]);
+
+    let proof = IntersectionProof {
+        cardinality,
+        intersection: Commitment(intersection_commitment),
+        degree,
+        cofactors: [cofactor_a_commitment.0, cofactor_b_commitment.0],
+        bezout: [bezout_a_commitment.0, bezout_b_commitment.0],
+        evaluations,
+        witness,
+        products,
+        product_proofs,
+        nonce,
+        response
+    };
+
+    Ok((intersection, proof))
+}
+
+
+/// Verifies a proof of the size of the intersection of two committed sets
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment_a` - The commitment to the first set
+/// * `commitment_b` - The commitment to the second set
+/// * `proof` - The proof returned by `prove_intersection`
+///
+/// # Returns
+///
+/// `Ok(())` if the sets have exactly `proof.cardinality` elements in common
+/// and `proof.intersection` is a commitment to the intersection
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if the cardinality is larger than the degree of the public key
+/// * `KzgError::MalformedProof` if the proof for an empty intersection does not commit to I = 1
+/// * `KzgError::PairingCheckFailed` if the degree or the evaluations do not match the commitments
+/// * `KzgError::SchnorrFailed` if one of the products is wrong
+///
+pub fn verify_intersection<T: Curve>(pk: &Pk<T>, commitment_a: &Commitment<T>, commitment_b: &Commitment<T>, proof: &IntersectionProof<T>) -> Result<(), KzgError> {
+    let max = pk.g_powers.len() - 1;
+    let cardinality = proof.cardinality;
+    if cardinality > max {
+        return Err(KzgError::SetTooLarge { max, got: cardinality });
+    }
+
+    let zero = T::scalar_from_literal(&0);
+    let identity = T::g1mul(&zero, &T::g1());
+
+    // e(C_I · g^-(α^k), g2^(α^(d + 1 - k))) = e(D, g2)
+    if cardinality == 0 {
+        if proof.intersection.0 != T::g1() || proof.degree != identity {
+            return Err(KzgError::MalformedProof);
+        }
+    } else {
+        let lower = T::g1sub(&proof.intersection.0, &pk.g_powers[max - cardinality]);
+        let left = T::pairing(&lower, &pk.g2_powers[cardinality - 1]);
+        let right = T::pairing(&proof.degree, &T::g2());
+        if left != right {
+            return Err(KzgError::PairingCheckFailed);
+        }
+    }
+
+    let commitments = [
+        commitment_a.0, commitment_b.0, proof.intersection.0,
+        proof.cofactors[0], proof.cofactors[1], proof.bezout[0], proof.bezout[1]
+    ];
+
+    // the challenges are bound to the claimed cardinality
+    let bound = [T::scalar_from_literal(&(cardinality as u128))];
+    let mut transcript = commitments.to_vec();
+    transcript.push(proof.degree);
+    let z = transcript_challenge::<T>(b"kzg intersection point", &bound, &transcript, &[]);
+
+    transcript.extend_from_slice(&proof.evaluations);
+    let gamma = transcript_challenge::<T>(b"kzg intersection evaluations", &bound, &transcript, &[]);
+
+    transcript.push(proof.witness);
+    transcript.extend_from_slice(&proof.products);
+    transcript.extend(proof.product_proofs.iter().flat_map(|p| p.nonces));
+    transcript.push(proof.nonce);
+    let c = transcript_challenge::<T>(b"kzg intersection products", &bound, &transcript, &[]);
+
+    // e(w, g2^(α - z)) = e(∏ (C_i · Y_i^-1)^(γ^i), g2)
+    let differences: Vec<_> = commitments.iter().zip(proof.evaluations.iter()).map(|(c, y)| T::g1sub(c, y)).collect();
+    let combined = T::msm_g1(&powers::<T>(gamma, POLYNOMIALS), &differences);
+    let left = T::pairing(&proof.witness, &T::g2sub(&pk.alpha_g2, &T::g2mul(&z, &T::g2())));
+    let right = T::pairing(&combined, &T::g2());
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+
+    let evaluations = &proof.evaluations;
+    let statements = [
+        (evaluations[INTERSECTION], evaluations[COFACTOR_A], evaluations[PHI_A]),
+        (evaluations[INTERSECTION], evaluations[COFACTOR_B], evaluations[PHI_B]),
+        (evaluations[BEZOUT_A], evaluations[COFACTOR_A], proof.products[0]),
+        (evaluations[BEZOUT_B], evaluations[COFACTOR_B], proof.products[1])
+    ];
+
+    // g^s1 · h^s2 · Y^c = g^k1 · h^k2 and X^s1 · h^s3 · Z^c = X^k1 · h^k3
+    for ((x, y, z), product_proof) in statements.iter().zip(proof.product_proofs.iter()) {
+        let [s1, s2, s3] = product_proof.responses;
+        let first = T::g1add(&pedersen(pk, s1, s2), &T::g1mul(&c, y));
+        let second = T::g1add(&T::g1add(&T::g1mul(&s1, x), &T::g1mul(&s3, &pk.h1)), &T::g1mul(&c, z));
+        if first != product_proof.nonces[0] || second != product_proof.nonces[1] {
+            return Err(KzgError::SchnorrFailed);
+        }
+    }
+
+    // a(z)·P_A(z) + b(z)·P_B(z) = 1, so the sum of the products is g · h^r
+    let sum = T::g1sub(&T::g1add(&proof.products[0], &proof.products[1]), &T::g1());
+    if T::g1add(&T::g1mul(&proof.response, &pk.h1), &T::g1mul(&c, &sum)) != proof.nonce {
+        return Err(KzgError::SchnorrFailed);
+    }
+
+    Ok(())
+}
+
+
+// g^value · h^blinding
+fn pedersen<T: Curve>(pk: &Pk<T>, value: T::Scalar, blinding: T::Scalar) -> T::G1 {
+    T::g1add(&T::g1mul(&value, &T::g1()), &T::g1mul(&blinding, &pk.h1))
+}
+
+// 1, x, x^2, ..., x^(n-1)
+fn powers<T: Curve>(x: T::Scalar, n: usize) -> Vec<T::Scalar> {
+    let mut result = Vec::with_capacity(n);
+    let mut current = T::scalar_from_literal(&1);
+    for _ in 0..n {
+        result.push(current);
+        current = current * x;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+    use crate::tape::{random_scalars, setup_tape};
+    use crate::{commitzk, queryzk, setup, verifyzk};
+    use super::*;
+
+    // this tests completeness of intersection proofs and that the intersection commitment can be queried
+    #[quickcheck]
+    fn test_intersection(common: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let degree = 8;
+        let common = (common % 5) as usize;
+
+        let shared: Vec<_> = random_scalars::<Curve, _>(common);
+        let a: HashSet<_> = shared.iter().copied().chain(random_scalars::<Curve, Vec<_>>(3)).collect();
+        let b: HashSet<_> = shared.iter().copied().chain(random_scalars::<Curve, Vec<_>>(2)).collect();
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment_a, opening_a) = commitzk(&pk, &a, &mut random).expect("set fits the public key");
+        let (commitment_b, opening_b) = commitzk(&pk, &b, &mut random).expect("set fits the public key");
+
+        let mut rng = rand::rng();
+        let (intersection, proof) = prove_intersection(&pk, &opening_a, &opening_b, &mut rng).expect("openings fit the public key");
+
+        let membership = shared.iter().all(|s| {
+            let query = queryzk(&pk, &intersection, *s, &mut rng).expect("opening fits the public key");
+            query.is_member() && verifyzk(&pk, &proof.intersection, *s, &query).is_ok()
+        });
+
+        proof.cardinality == common
+            && membership
+            && verify_intersection(&pk, &commitment_a, &commitment_b, &proof).is_ok()
+    }
+
+    #[test]
+    fn test_invalid_intersection_proofs() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(4, &mut rng);
+
+        let a: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let b: HashSet<_> = [2, 3, 4].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment_a, opening_a) = commitzk(&pk, &a, &mut rng).expect("set fits the public key");
+        let (commitment_b, opening_b) = commitzk(&pk, &b, &mut rng).expect("set fits the public key");
+        let (_, proof) = prove_intersection(&pk, &opening_a, &opening_b, &mut rng).expect("openings fit the public key");
+
+        // the cardinality cannot be changed
+        let mut forged = proof.clone();
+        forged.cardinality = 1;
+        assert_eq!(verify_intersection(&pk, &commitment_a, &commitment_b, &forged), Err(KzgError::PairingCheckFailed));
+        forged.cardinality = 5;
+        assert_eq!(verify_intersection(&pk, &commitment_a, &commitment_b, &forged), Err(KzgError::SetTooLarge { max: 4, got: 5 }));
+
+        // the proof is bound to both commitments
+        let (other, _) = commitzk(&pk, &b, &mut rng).expect("set fits the public key");
+        assert!(verify_intersection(&pk, &commitment_a, &other, &proof).is_err());
+
+        let mut forged = proof.clone();
+        forged.products.swap(0, 1);
+        assert!(verify_intersection(&pk, &commitment_a, &commitment_b, &forged).is_err());
+
+        // disjoint sets have an empty intersection
+        let c: HashSet<_> = [5, 6].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment_c, opening_c) = commitzk(&pk, &c, &mut rng).expect("set fits the public key");
+        let (_, proof) = prove_intersection(&pk, &opening_a, &opening_c, &mut rng).expect("openings fit the public key");
+        assert_eq!(proof.cardinality, 0);
+        assert!(verify_intersection(&pk, &commitment_a, &commitment_c, &proof).is_ok());
+
+        // openings that are not of a set are rejected instead of panicking
+        let zero = Opening { phi: vec![Curve::scalar_from_literal(&0); 3], phi_hat: opening_b.phi_hat.clone() };
+        assert_eq!(prove_intersection(&pk, &opening_a, &zero, &mut rng).err(), Some(KzgError::MalformedOpening));
+        let mut scaled = opening_b.clone();
+        scaled.phi = scaled.phi.iter().map(|x| *x * Curve::scalar_from_literal(&2)).collect();
+        assert_eq!(prove_intersection(&pk, &opening_a, &scaled, &mut rng).err(), Some(KzgError::MalformedOpening));
+    }
+}
@@ -0,0 +1,289 @@
+//! # Evaluation Form
+//!
+//! Commitments to polynomials given by their evaluations over the roots of unity of a power
+//! of two order n, without interpolating the coefficients first.
+//!
+//! `LagrangeBasis::new` turns the first n powers of the public key into the points
+//! g^L_i(α) for the Lagrange polynomials L_i of the domain ω^0, ..., ω^(n-1), with an inverse
+//! transform over the group. Since f(x) = Σ f(ω^i) · L_i(x), the commitment Π g^(f(ω^i) · L_i(α))
+//! equals the monomial commitment g^f(α) of `pcs::commit_public`, so openings are checked
+//! with `pcs::verify_public`.
+//!
+//! Outside of the domain f(z) is computed with the barycentric formula
+//! f(z) = (z^n - 1) / n · Σ f(ω^i) · ω^i / (z - ω^i).
+//!
+//! ```
+//! use kzg::{setup, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::lagrange::{commit_evaluations, open_evaluations, LagrangeBasis};
+//! use kzg::pcs::verify_public;
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(7, &mut rng);
+//! let basis = LagrangeBasis::new(&pk, 3).expect("domain fits the public key");
+//!
+//! let evaluations: Vec<_> = (1..=8).map(|x| FastCurve::scalar_from_literal(&x)).collect();
+//! let commitment = commit_evaluations(&basis, &evaluations).expect("one evaluation per root");
+//!
+//! // on the domain the opening is the evaluation
+//! let (y, proof) = open_evaluations(&basis, &evaluations, basis.domain()[2]).expect("one evaluation per root");
+//! assert_eq!(y, evaluations[2]);
+//! assert!(verify_public(&pk, &commitment, basis.domain()[2], y, proof).is_ok());
+//!
+//! let z = FastCurve::scalar_from_literal(&100);
+//! let (y, proof) = open_evaluations(&basis, &evaluations, z).expect("one evaluation per root");
+//! assert!(verify_public(&pk, &commitment, z, y, proof).is_ok());
+//! ```
+
+use crate::curve::Curve;
+use crate::{poly, Commitment, KzgError, Pk};
+
+
+/// The Lagrange form of the public key over a domain of roots of unity
+///
+/// # Fields
+///
+/// * `g_lagrange` - g^L_i(α) for the Lagrange polynomials of the domain
+/// * `domain` - The roots of unity ω^0, ..., ω^(n-1)
+pub struct LagrangeBasis<T: Curve> {
+    g_lagrange: Vec<T::G1>,
+    domain: Vec<T::Scalar>
+}
+
+impl<T: Curve> LagrangeBasis<T> {
+    /// Computes the Lagrange basis of the domain of size 2^log_size
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key
+    /// * `log_size` - The logarithm of the size of the domain
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::SetTooLarge` if the polynomials of the domain have a larger degree than the public key
+    ///
+    pub fn new(pk: &Pk<T>, log_size: u32) -> Result<Self, KzgError> {
+        let degree = pk.g_powers.len() - 1;
+        if log_size >= usize::BITS || (1 << log_size) > pk.g_powers.len() {
+            return Err(KzgError::SetTooLarge { max: degree, got: 1usize.checked_shl(log_size).map_or(usize::MAX, |n| n - 1) });
+        }
+        let size = 1 << log_size;
+
+        // g^(α^0), ..., g^(α^(n-1)) are the last powers of the key in reverse order
+        let powers: Vec<_> = pk.g_powers.iter().rev().take(size).copied().collect();
+
+        let omega = poly::root_of_unity::<T>(log_size);
+        let mut domain = Vec::with_capacity(size);
+        let mut root = T::scalar_from_literal(&1);
+        for _ in 0..size {
+            domain.push(root);
+            root = root * omega;
+        }
+
+        Ok(LagrangeBasis { g_lagrange: poly::lagrange_g1::<T>(&powers), domain })
+    }
+
+    /// The roots of unity ω^0, ..., ω^(n-1) of the domain
+    pub fn domain(&self) -> &[T::Scalar] {
+        &self.domain
+    }
+}
+
+
+/// Commits to a polynomial given by its evaluations over the domain
+///
+/// # Arguments
+///
+/// * `basis` - The Lagrange basis
+/// * `evaluations` - f(ω^0), ..., f(ω^(n-1))
+///
+/// # Returns
+///
+/// The commitment C = g^f(α), the same as `pcs::commit_public` of the coefficients
+///
+/// # Errors
+///
+/// `KzgError::MalformedOpening` if there is not one evaluation per root of unity
+///
+pub fn commit_evaluations<T: Curve>(basis: &LagrangeBasis<T>, evaluations: &[T::Scalar]) -> Result<Commitment<T>, KzgError> {
+    if evaluations.len() != basis.domain.len() {
+        return Err(KzgError::MalformedOpening);
+    }
+    Ok(Commitment(T::msm_g1(evaluations, &basis.g_lagrange)))
+}
+
+
+/// Evaluates a polynomial given by its evaluations over the domain
+///
+/// # Arguments
+///
+/// * `basis` - The Lagrange basis
+/// * `evaluations` - f(ω^0), ..., f(ω^(n-1))
+/// * `z` - The evaluation point
+///
+/// # Returns
+///
+/// f(z), computed with the barycentric formula outside of the domain
+///
+/// # Errors
+///
+/// `KzgError::MalformedOpening` if there is not one evaluation per root of unity
+///
+pub fn evaluate<T: Curve>(basis: &LagrangeBasis<T>, evaluations: &[T::Scalar], z: T::Scalar) -> Result<T::Scalar, KzgError> {
+    if evaluations.len() != basis.domain.len() {
+        return Err(KzgError::MalformedOpening);
+    }
+    Ok(barycentric::<T>(&basis.domain, evaluations, z))
+}
+
+
+/// Opens a polynomial given by its evaluations over the domain at a point
+///
+/// # Arguments
+///
+/// * `basis` - The Lagrange basis
+/// * `evaluations` - f(ω^0), ..., f(ω^(n-1))
+/// * `z` - The evaluation point, inside or outside of the domain
+///
+/// # Returns
+///
+/// The evaluation y = f(z) and the witness w = g^ψ(α) for ψ = (f - y) / (x - z),
+/// checked with `pcs::verify_public`
+///
+/// # Errors
+///
+/// `KzgError::MalformedOpening` if there is not one evaluation per root of unity
+///
+pub fn open_evaluations<T: Curve>(basis: &LagrangeBasis<T>, evaluations: &[T::Scalar], z: T::Scalar) -> Result<(T::Scalar, T::G1), KzgError> {
+    let y = evaluate(basis, evaluations, z)?;
+    let psi = quotient::<T>(&basis.domain, evaluations, z, y);
+    Ok((y, T::msm_g1(&psi, &basis.g_lagrange)))
+}
+
+
+// f(z) for the evaluations of f over all roots of unity of order n, in any order
+pub(crate) fn barycentric<T: Curve>(domain: &[T::Scalar], evaluations: &[T::Scalar], z: T::Scalar) -> T::Scalar {
+    if let Some(i) = domain.iter().position(|root| *root == z) {
+        return evaluations[i];
+    }
+
+    let mut inverses: Vec<_> = domain.iter().map(|root| z - *root).collect();
+    poly::batch_inverse::<T>(&mut inverses);
+
+    let mut result = T::scalar_from_literal(&0);
+    for ((f, root), inverse) in evaluations.iter().zip(domain).zip(inverses) {
+        result = result + *f * *root * inverse;
+    }
+
+    // (z^n - 1) / n
+    let one = T::scalar_from_literal(&1);
+    let n = domain.len() as u128;
+    result * (T::scalar_pow(&z, &n) - one) * T::scalar_inv(&T::scalar_from_literal(&n))
+}
+
+// the evaluations of ψ = (f - y) / (x - z) over the same roots of unity
+pub(crate) fn quotient<T: Curve>(domain: &[T::Scalar], evaluations: &[T::Scalar], z: T::Scalar, y: T::Scalar) -> Vec<T::Scalar> {
+    let one = T::scalar_from_literal(&1);
+    let position = domain.iter().position(|root| *root == z);
+
+    let mut inverses: Vec<_> = domain.iter().map(|root| if *root == z { one } else { *root - z }).collect();
+    poly::batch_inverse::<T>(&mut inverses);
+    let mut psi: Vec<_> = evaluations.iter().zip(inverses).map(|(f, inverse)| (*f - y) * inverse).collect();
+
+    // at z = ω_m the formula divides by zero, instead
+    // ψ(ω_m) = f'(ω_m) = Σ_{i ≠ m} (f(ω_i) - y) · ω_i / (z · (z - ω_i))
+    if let Some(m) = position {
+        let mut inverses: Vec<_> = domain.iter().map(|root| if *root == z { one } else { z * (z - *root) }).collect();
+        poly::batch_inverse::<T>(&mut inverses);
+
+        let mut derivative = T::scalar_from_literal(&0);
+        for (i, ((f, root), inverse)) in evaluations.iter().zip(domain).zip(inverses).enumerate() {
+            if i != m {
+                derivative = derivative + (*f - y) * *root * inverse;
+            }
+        }
+        psi[m] = derivative;
+    }
+    psi
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::pcs::{commit_public, verify_public};
+    use crate::{apply, setup};
+    use super::*;
+
+    // the Lagrange form commits to the same point as the coefficients
+    #[quickcheck]
+    fn test_commit_evaluations(polynomial: Vec<u128>, log_size: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(8, &mut rng);
+        let log_size = (log_size % 4) as u32;
+        let basis = LagrangeBasis::new(&pk, log_size).expect("domain fits the public key");
+
+        let polynomial: Vec<_> = polynomial.iter().take(1 << log_size).map(Curve::scalar_from_literal).collect();
+        if polynomial.is_empty() {
+            return commit_evaluations(&basis, &polynomial).err() == Some(KzgError::MalformedOpening);
+        }
+        let evaluations: Vec<_> = basis.domain().iter().map(|x| apply::<Curve>(&polynomial, x)).collect();
+
+        commit_evaluations(&basis, &evaluations) == commit_public(&pk, &polynomial)
+    }
+
+    // this tests completeness and soundness of openings inside and outside of the domain
+    #[quickcheck]
+    fn test_open_evaluations(evaluations: Vec<u128>, z: u128, index: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(8, &mut rng);
+        let basis = LagrangeBasis::new(&pk, 3).expect("domain fits the public key");
+
+        let mut evaluations: Vec<_> = evaluations.iter().map(Curve::scalar_from_literal).collect();
+        evaluations.resize(8, Curve::scalar_from_literal(&0));
+        let commitment = commit_evaluations(&basis, &evaluations).expect("one evaluation per root");
+
+        let one = Curve::scalar_from_literal(&1);
+        [Curve::scalar_from_literal(&z), basis.domain()[index as usize % 8]].iter().all(|z| {
+            let (y, proof) = open_evaluations(&basis, &evaluations, *z).expect("one evaluation per root");
+            verify_public(&pk, &commitment, *z, y, proof).is_ok()
+                && verify_public(&pk, &commitment, *z, y + one, proof) == Err(KzgError::PairingCheckFailed)
+        })
+    }
+
+    // a domain of a single root, where the bit reversal permutes nothing
+    #[test]
+    fn test_single_root() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(2, &mut rng);
+        let basis = LagrangeBasis::new(&pk, 0).expect("domain fits the public key");
+        assert_eq!(basis.domain(), &[Curve::scalar_from_literal(&1)]);
+
+        let evaluations = vec![Curve::scalar_from_literal(&5)];
+        let commitment = commit_evaluations(&basis, &evaluations).expect("one evaluation per root");
+        assert_eq!(Ok(commitment), commit_public(&pk, &evaluations));
+
+        let z = Curve::scalar_from_literal(&3);
+        let (y, proof) = open_evaluations(&basis, &evaluations, z).expect("one evaluation per root");
+        assert_eq!(y, evaluations[0]);
+        assert!(verify_public(&pk, &commitment, z, y, proof).is_ok());
+    }
+
+    #[test]
+    fn test_domain_too_large() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(6, &mut rng);
+
+        assert!(LagrangeBasis::new(&pk, 2).is_ok());
+        assert_eq!(LagrangeBasis::new(&pk, 3).err(), Some(KzgError::SetTooLarge { max: 6, got: 7 }));
+        assert_eq!(LagrangeBasis::new(&pk, 64).err(), Some(KzgError::SetTooLarge { max: 6, got: usize::MAX }));
+    }
+}
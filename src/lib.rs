@@ -21,29 +21,29 @@
 //! use kzg::curve::FastCurve as CurveImpl;
 //!
 //! // Setup phase
-//! let mut randomness = vec![42u128; 10]; // In practice, use cryptographically secure randomness
-//! let pk: Pk<CurveImpl> = setup(5, &mut randomness);
+//! let mut rng = rand::rng();
+//! let pk: Pk<CurveImpl> = setup(5, &mut rng);
 //!
 //! // Create a set and commit to it
 //! let mut set = HashSet::new();
 //! set.insert(CurveImpl::scalar_from_literal(&123));
 //! set.insert(CurveImpl::scalar_from_literal(&456));
 //!
-//! let (commitment, phi, phi_hat) = commitzk(&pk, &set, &mut randomness);
+//! let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
 //!
 //! // Query for membership
 //! let query_element = CurveImpl::scalar_from_literal(&123);
-//! let (kj, witness, phi_hat_kj, pi_sj) = queryzk(&pk, &set, &phi, &phi_hat, 
-//!                                               query_element, &mut randomness);
+//! let proof = queryzk(&pk, &opening, query_element, &mut rng).expect("opening fits the public key");
+//! assert!(proof.is_member());
 //!
 //! // Verify the proof
-//! let is_valid = verifyzk(&pk, commitment, pi_sj, kj, witness, phi_hat_kj);
-//! assert!(is_valid);
+//! let result = verifyzk(&pk, &commitment, query_element, &proof);
+//! assert!(result.is_ok());
 //! ```
 //!
 //! ## Security Properties
 //! 
-//! If the provided randomness is suited for cryptographic applications then
+//! If the provided random number generator is suited for cryptographic applications then
 //! the following security properties apply
 //! 
 //! - **Zero-knowledge**: Proofs reveal no information about the set beyond membership
@@ -58,18 +58,45 @@
 //!
 //! ```
 //! use kzg::{Pk, setup};
-//! let mut randomness = vec![10; 4];
+//! let mut rng = rand::rng();
 //! let degree = 4;
 //! 
 //! // Using the specification curve
 //! use kzg::curve::SpecCurve as SpecCurve;
-//! let pk: Pk<SpecCurve> = setup(degree, &mut randomness);
+//! let pk: Pk<SpecCurve> = setup(degree, &mut rng);
 //!
 //! // Using the fast curve
 //! use kzg::curve::FastCurve as FastCurve;
-//! let pk: Pk<FastCurve> = setup(degree, &mut randomness);
+//! let pk: Pk<FastCurve> = setup(degree, &mut rng);
 //! ```
 //!
+//! ### Deterministic Randomness
+//!
+//! Any `RngCore + CryptoRng` can be used as the source of randomness.
+//! The tests replay a fixed list of `u128` values instead, to run both curve
+//! implementations on the same randomness. That replay is only compiled for the tests,
+//! so predictable randomness cannot reach the protocol functions from outside the crate.
+//!
+//! ### Errors
+//!
+//! Fallible functions return a `KzgError` describing what went wrong,
+//! for example `KzgError::SetTooLarge` if a set does not fit the public key
+//! or `KzgError::PairingCheckFailed` if a proof is rejected.
+//!
+//! ### Serialization
+//!
+//! `Pk`, `Commitment`, `Opening` and the proof types have `to_bytes` and `from_bytes` methods
+//! built on compressed point encodings. Both curve implementations produce identical bytes.
+//!
+//! With the `serde` feature these types also implement `Serialize` and `Deserialize`,
+//! as hex strings in human readable formats and as raw bytes in binary formats.
+//!
+//! ### Parallelism
+//!
+//! With the `parallel` feature `setup`, committing and the set polynomial split their work
+//! across rayon threads, and the powers of the public key use fixed-base windowed
+//! multiplication. The results are the same as without the feature.
+//!
 //! ## Dependencies
 //!
 //! This library depends on:
@@ -79,12 +106,39 @@
 //! - `blstrs` for fast elliptic curve operations
 //! 
 
+pub mod ceremony;
 pub mod curve;
+pub mod disjoint;
+pub mod eip4844;
+pub mod intersection;
+pub mod lagrange;
+pub mod multiset;
+pub mod pcs;
+pub mod poly;
+pub mod subset;
+pub mod update;
+mod error;
+mod parallel;
+mod ptau;
+mod serialization;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(test)]
+mod tape;
 use curve::Curve;
+pub use error::KzgError;
 use hacspec_lib::*;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
 use std::collections::HashSet;
+use std::fmt;
+
 
 
+// the domain separation tag and message the hiding generator h is hashed from
+const HIDING_GENERATOR_DST: &[u8] = b"KZG-SET-COMMITMENTS-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+const HIDING_GENERATOR_MESSAGE: &[u8] = b"hiding generator";
+
 
 /// Public key structure containing the public key parameters
 /// 
@@ -94,11 +148,13 @@ use std::collections::HashSet;
 /// 
 /// * `g_powers` - Powers of generator g: [g^(α^d), g^(α^(d-1)), ..., g^α, g]
 /// * `h_powers` - Powers of hiding generator h: [h^(α^d), h^(α^(d-1)), ..., h^α, h]  
+/// * `g2_powers` - Powers of generator g2: [g2^(α^d), g2^(α^(d-1)), ..., g2^α, g2]
 /// * `h1` - The hiding generator h
 /// * `alpha_g2` - α hidden by the generator g2
 pub struct Pk<T: Curve> {
     g_powers: Vec<T::G1>,
     h_powers: Vec<T::G1>,
+    g2_powers: Vec<T::G2>,
     h1: T::G1,
     alpha_g2: T::G2
 }
@@ -107,46 +163,246 @@ pub struct Pk<T: Curve> {
 /// Runs the trusted authority setup phase 
 ///
 /// Creates the public key used in the protocol
-/// Securely deletes α and λ to ensure soundness 
+/// Securely deletes α to ensure soundness 
+///
+/// The hiding generator h is hashed to the curve from a fixed message, so nobody,
+/// including the authority, knows log_g(h)
 ///
 /// # Arguments
 ///
 /// * `degree` - Maximum degree of polynomials that can be committed to
-/// * `random` - Mutable vector of random values used for parameter generation
+/// * `rng` - Cryptographically secure random number generator
 ///
 /// # Returns
 ///
 /// A `Pk<T>` structure containing the public parameters
 ///
-/// # Panics
+pub fn setup<T: Curve, R: RngCore + CryptoRng>(degree: u128, rng: &mut R) -> Pk<T> {
+
+    let alpha = T::random_scalar(rng);
+
+    // α^d, α^(d-1), ..., α, 1, each power from the one below
+    let mut alpha_powers = Vec::with_capacity(degree as usize + 1);
+    let mut alpha_power = T::scalar_from_literal(&1);
+    for _ in 0..degree + 1 {
+        alpha_powers.push(alpha_power);
+        alpha_power = alpha_power * alpha;
+    }
+    alpha_powers.reverse();
+    
+    // nothing up my sleeve: h does not depend on any secret
+    let h = T::hash_to_g1(HIDING_GENERATOR_DST, HIDING_GENERATOR_MESSAGE);
+
+    let (setup_g1, setup_h1) = parallel::join(
+        || parallel::batch_mul_g1::<T>(&T::g1(), &alpha_powers),
+        || parallel::batch_mul_g1::<T>(&h, &alpha_powers)
+    );
+    let setup_g2 = parallel::batch_mul_g2::<T>(&T::g2(), &alpha_powers);
+    
+    let alpha_g2 = T::g2mul(&alpha, &T::g2());
+
+    Pk{g_powers : setup_g1, h_powers : setup_h1, g2_powers : setup_g2, h1 : h, alpha_g2}
+}
+
+impl<T: Curve> Pk<T> {
+    /// Checks that the public key is well formed
+    ///
+    /// The powers of g, h and g2 have to start at the generators and step by the α that
+    /// `alpha_g2` encodes. Instead of one pairing per power, the steps are checked for a
+    /// random linear combination, which takes two multi-pairings for any degree. The
+    /// coefficients are powers of a challenge hashed from the key, so a malformed key
+    /// passes with probability at most 2d / r.
+    ///
+    /// # Errors
+    ///
+    /// * `KzgError::InvalidEncoding` if the powers have different lengths or a point is the identity or outside the prime order subgroup
+    /// * `KzgError::PairingCheckFailed` if the powers do not step by the same α
+    ///
+    pub fn verify(&self) -> Result<(), KzgError> {
+        if self.g_powers.is_empty() || self.h_powers.len() != self.g_powers.len() || self.g2_powers.len() != self.g_powers.len() {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let d = self.g_powers.len() - 1;
+
+        // decoding the encoding of a point checks that it is in the subgroup
+        let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+        let identity_g2 = T::g2mul(&T::scalar_from_literal(&0), &T::g2());
+        let mut hasher = Sha512::new();
+        hasher.update(b"kzg public key");
+        for point in self.g_powers.iter().chain(&self.h_powers).chain([&self.h1]) {
+            let bytes = T::g1_to_bytes(point);
+            if *point == identity || T::g1_from_bytes(&bytes) != Some(*point) {
+                return Err(KzgError::InvalidEncoding);
+            }
+            hasher.update(bytes);
+        }
+        for point in self.g2_powers.iter().chain([&self.alpha_g2]) {
+            let bytes = T::g2_to_bytes(point);
+            if *point == identity_g2 || T::g2_from_bytes(&bytes) != Some(*point) {
+                return Err(KzgError::InvalidEncoding);
+            }
+            hasher.update(bytes);
+        }
+
+        if self.g_powers[d] != T::g1() || self.g2_powers[d] != T::g2() || self.h_powers[d] != self.h1 {
+            return Err(KzgError::PairingCheckFailed);
+        }
+        if d == 0 {
+            return Ok(());
+        }
+        if self.g2_powers[d - 1] != self.alpha_g2 {
+            return Err(KzgError::PairingCheckFailed);
+        }
+
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&hasher.finalize());
+        let rho = T::scalar_from_bytes_wide(&bytes);
+        let mut r = Vec::with_capacity(2 * d);
+        let mut power = T::scalar_from_literal(&1);
+        for _ in 0..2 * d {
+            r.push(power);
+            power = power * rho;
+        }
+
+        // e(Σ r_i P_(i+1), g2^α) = e(Σ r_i P_i, g2) for the steps P_i, P_(i+1) of both
+        // g^(α^(d-i)) and h^(α^(d-i))
+        let lower: Vec<_> = self.g_powers[1..].iter().chain(&self.h_powers[1..]).copied().collect();
+        let upper: Vec<_> = self.g_powers[..d].iter().chain(&self.h_powers[..d]).copied().collect();
+        let pairs = [(T::msm_g1(&r, &lower), self.alpha_g2), (T::g1sub(&identity, &T::msm_g1(&r, &upper)), T::g2())];
+        if T::multi_pairing(&pairs) != T::gt_one() {
+            return Err(KzgError::PairingCheckFailed);
+        }
+
+        // e(g^α, Σ r_i Q_(i+1)) = e(g, Σ r_i Q_i) for Q_i = g2^(α^(d-i))
+        let lower = T::msm_g2(&r[..d], &self.g2_powers[1..]);
+        let upper = T::msm_g2(&r[..d], &self.g2_powers[..d]);
+        let pairs = [(self.g_powers[d - 1], lower), (T::g1sub(&identity, &T::g1()), upper)];
+        if T::multi_pairing(&pairs) != T::gt_one() {
+            return Err(KzgError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+}
+
+/// Commitment to a set
+///
+/// Wraps the group element C = g^φ(α) · h^phi_hat(α) published by the prover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment<T: Curve>(pub T::G1);
+
+
+/// Opening of a commitment
 ///
-/// Panics if random.len() < 2 
+/// This is the secret information the prover keeps to answer queries.
 ///
-pub fn setup<T: Curve>(degree: u128, random: &mut Vec<u128>) -> Pk<T> {
+/// # Fields
+///
+/// * `phi` - The polynomial φ(x) = ∏(x - s) for all s in the set
+/// * `phi_hat` - The random hiding polynomial phi_hat(x)
+#[derive(Clone, PartialEq, Eq)]
+pub struct Opening<T: Curve> {
+    pub phi: Vec<T::Scalar>,
+    pub phi_hat: Vec<T::Scalar>
+}
 
-    let rand = random.pop().expect("not enough randomness provided");
-    let alpha =  T::scalar_from_literal(&rand);
+// φ reveals the set, so neither polynomial is printed
+impl<T: Curve> fmt::Debug for Opening<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Opening").finish_non_exhaustive()
+    }
+}
 
-    let mut setup_g1 = Vec::new();
-    let mut setup_h1 = Vec::new();
-    
-    // generate h from some random lambda 
-    let rand = random.pop().expect("not enough randomness provided");
-    let h = T::g1mul(&T::scalar_from_literal(&rand), &T::g1());
 
-    for i in 0..degree + 1 {
-        let power: u128 = (degree - i).into();
-        let alpha_power= T::scalar_pow(&alpha, &power);
+/// Proof of membership or non-membership of a queried element
+///
+/// # Variants
+///
+/// * `Member` - kj is in the set
+///     * `witness` - The witness w = g^ψ(α) · h^psi_hat(α)
+///     * `phi_hat_kj` - The evaluation phi_hat(kj)
+/// * `NonMember` - kj is not in the set
+///     * `witness` - The witness w = g^ψ(α) · h^psi_hat(α)
+///     * `proof` - g^φ(kj) · h^phi_hat(kj)
+///     * `n1`, `n2`, `s1`, `s2` - Schnorr proof of knowledge of φ(kj) ≠ 0 and phi_hat(kj)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MembershipProof<T: Curve> {
+    Member {
+        witness: T::G1,
+        phi_hat_kj: T::Scalar
+    },
+    NonMember {
+        witness: T::G1,
+        proof: T::G1,
+        n1: T::G1,
+        n2: T::G1,
+        s1: T::Scalar,
+        s2: T::Scalar
+    }
+}
+
+impl<T: Curve> MembershipProof<T> {
+    /// Returns `true` if the proof claims that the queried element is in the set
+    pub fn is_member(&self) -> bool {
+        matches!(self, MembershipProof::Member { .. })
+    }
+
+    /// Returns the witness w = g^ψ(α) · h^psi_hat(α)
+    pub fn witness(&self) -> T::G1 {
+        match self {
+            MembershipProof::Member { witness, .. } => *witness,
+            MembershipProof::NonMember { witness, .. } => *witness
+        }
+    }
+}
+
+
+/// Proof that several queried elements are all in the set
+///
+/// Dividing φ(x) and phi_hat(x) by the vanishing polynomial Z(x) = ∏(x - kj) gives
+/// φ = q·Z and phi_hat = q_hat·Z + r_hat, since every kj is a root of φ.
+///
+/// # Fields
+///
+/// * `witness` - The aggregated witness w = g^q(α) · h^q_hat(α)
+/// * `remainder` - The remainder r_hat(x), one coefficient per queried element
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchMembershipProof<T: Curve> {
+    pub witness: T::G1,
+    pub remainder: Vec<T::Scalar>
+}
 
-        setup_g1.push(T::g1mul(&alpha_power, &T::g1()));
-        setup_h1.push(T::g1mul(&alpha_power, &h));
-    };
-    
-    let alpha_g2 = T::g2mul(&alpha, &T::g2());
 
-    Pk{g_powers : setup_g1, h_powers : setup_h1, h1 : h, alpha_g2}
+/// Proof that several queried elements are all not in the set
+///
+/// Dividing φ(x) by the vanishing polynomial Z(x) = ∏(x - kj) gives φ = q·Z + r. No kj
+/// is a root of φ exactly when r has an inverse u modulo Z, that is r·u = 1 + t·Z.
+/// The prover blinds r and u with multiples of Z, commits to r, u and t and opens the
+/// three commitments at a random point z, where the verifier checks the identity.
+/// The proof has the same size for any number of queried elements.
+///
+/// # Fields
+///
+/// * `witness` - The aggregated witness w = g^q'(α) · h^q_hat(α), with q' = q - ρ
+/// * `remainder` - The commitment g^r'(α) · h^r_hat(α) to the blinded remainder r' = r + ρ·Z
+/// * `inverse` - The commitment g^u'(α) · h^u_hat(α) to the blinded inverse u' = u + σ·Z
+/// * `cofactor` - The commitment g^t'(α) · h^t_hat(α) to t' = (r'·u' - 1) / Z
+/// * `opening` - The witness of the opening of `remainder` · `inverse`^γ · `cofactor`^γ² at z
+/// * `remainder_z` - The evaluation r'(z)
+/// * `inverse_z` - The evaluation u'(z)
+/// * `hiding_z` - The evaluation r_hat(z) + γ·u_hat(z) + γ²·t_hat(z)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchNonMembershipProof<T: Curve> {
+    pub witness: T::G1,
+    pub remainder: T::G1,
+    pub inverse: T::G1,
+    pub cofactor: T::G1,
+    pub opening: T::G1,
+    pub remainder_z: T::Scalar,
+    pub inverse_z: T::Scalar,
+    pub hiding_z: T::Scalar
 }
 
+
 /// Creates a zero-knowledge commitment to a set
 ///
 /// This function takes a set of elements and creates a polynomial commitment that
@@ -156,45 +412,28 @@ pub fn setup<T: Curve>(degree: u128, random: &mut Vec<u128>) -> Pk<T> {
 ///
 /// * `pk` - The public key
 /// * `set` - The set of elements to commit to
-/// * `random` - Mutable vector of random values
+/// * `rng` - Cryptographically secure random number generator
 ///
 /// # Returns
 ///
 /// A tuple containing:
-/// * `T::G1` - The commitment C = g^φ(α) · h^phi_hat(α)
-/// * `Vec<T::Scalar>` - The polynomial φ(x)
-/// * `Vec<T::Scalar>` - The hiding polynomial phi_hat(x) 
+/// * `Commitment<T>` - The commitment C = g^φ(α) · h^phi_hat(α)
+/// * `Opening<T>` - The polynomials φ(x) and phi_hat(x) needed to answer queries
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if random.len() < set.len()
+/// `KzgError::SetTooLarge` if the set has more elements than the degree of the public key
 ///
-pub fn commitzk<T: Curve>(pk: &Pk<T>, set: &HashSet<T::Scalar>, random: &mut Vec<u128>) -> (T::G1, Vec<T::Scalar>, Vec<T::Scalar>) {
-    let mut phi = vec![T::scalar_from_literal(&1)];
-    
-    let zero = T::scalar_from_literal(&0);
-    
-    // Constructing the set is O(n^3) 
-    // could be optimized using horner's method
-    // or fft. but they are out of scope
-    for i in set {
-        let mul = vec![T::scalar_from_literal(&1),  zero - *i ];
-        phi = multiply::<T>(&phi, &mul);
+pub fn commitzk<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, set: &HashSet<T::Scalar>, rng: &mut R) -> Result<(Commitment<T>, Opening<T>), KzgError> {
+    let max = pk.g_powers.len() - 1;
+    if set.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: set.len() });
     }
 
-    let mut phi_hat = vec![T::scalar_from_literal(&0); phi.len()];
-    
-    // create a random hiding polynomial
-    for i in 0..phi_hat.len() {
-        let rand = random.pop().expect("not enough randomness provided");
-        phi_hat[i] = T::scalar_from_literal(&rand);
-    }
-    
-    let commitment = commit_poly::<T>(&phi, &pk.g_powers, T::g1());
-    let hiding_commitment = commit_poly::<T>(&phi_hat, &pk.h_powers, pk.h1);
+    // subproduct tree with NTT multiplication, O(n log^2 n)
+    let phi = poly::set_polynomial::<T>(set);
 
-    
-    (T::g1add(&commitment, &hiding_commitment), phi, phi_hat)
+    commit_hiding(pk, phi, rng)
 }
 
 
@@ -206,36 +445,35 @@ pub fn commitzk<T: Curve>(pk: &Pk<T>, set: &HashSet<T::Scalar>, random: &mut Vec
 /// polynomial evaluation. For elements not in the set, it provides a Schnorr proof
 /// that the polynomial evaluation is non-zero.
 ///
+/// The set itself is not needed since φ(kj) = 0 exactly when kj is in the set.
+///
 /// # Arguments
 ///
 /// * `pk` - The public key
-/// * `set` - The original set that was committed to
-/// * `phi` - The polynomial φ
-/// * `phi_hat` - The hiding polynomial phi_hat
+/// * `opening` - The opening returned by `commitzk`
 /// * `kj` - The queried element
-/// * `random` - Mutable vector of random values
+/// * `rng` - Cryptographically secure random number generator
 ///
 /// # Returns
 ///
-/// A tuple containing:
-/// * `T::Scalar` - The queried element kj
-/// * `T::G1` - The witness w = g^ψ(α) · h^psi_hat(α)
-/// * `Option<T::Scalar>` - psi_hat(kj) if element is in set, None otherwise
-/// * `Option<(T::G1, T::G1, T::G1, T::Scalar, T::Scalar)>` - Schnorr proof if element not in set, None otherwise
-///
+/// A `MembershipProof<T>` for kj
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if random.len() < 2 
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if the opening does not fit the public key
 ///
-pub fn queryzk<T: Curve>(pk: &Pk<T>, set: &HashSet<T::Scalar>, phi: &Vec<T::Scalar>, phi_hat: &Vec<T::Scalar>, kj: T::Scalar, random: &mut Vec<u128>)
--> (T::Scalar, T::G1, Option<T::Scalar>, Option<(T::G1, T::G1, T::G1, T::Scalar, T::Scalar)>) {
+pub fn queryzk<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening: &Opening<T>, kj: T::Scalar, rng: &mut R) -> Result<MembershipProof<T>, KzgError> {
+    check_opening(opening)?;
+    if opening.phi.len() > pk.g_powers.len() {
+        return Err(KzgError::SetTooLarge { max: pk.g_powers.len() - 1, got: opening.phi.len() - 1 });
+    }
 
-    let (kj, phi_kj, phi_hat_kj, witness) = create_witness(phi, phi_hat, kj, pk);
+    let (kj, phi_kj, phi_hat_kj, witness) = create_witness(&opening.phi, &opening.phi_hat, kj, pk)?;
     
     
-    if set.contains(&kj) {
-        return (kj, witness, Some(phi_hat_kj), None);
+    if phi_kj == T::scalar_from_literal(&0) {
+        return Ok(MembershipProof::Member { witness, phi_hat_kj });
     };
     
     let p1 = T::g1mul(&phi_kj, &T::g1());
@@ -244,9 +482,9 @@ pub fn queryzk<T: Curve>(pk: &Pk<T>, set: &HashSet<T::Scalar>, phi: &Vec<T::Scal
     
     let proof = T::g1add(&p1, &p2);
 
-    let (n1, n2, s1, s2) = schnorr_proof(pk, phi_kj, phi_hat_kj, random);
+    let (n1, n2, s1, s2) = schnorr_proof(pk, phi_kj, phi_hat_kj, rng);
 
-    return (kj, witness, None, Some((proof, n1, n2, s1, s2)));
+    Ok(MembershipProof::NonMember { witness, proof, n1, n2, s1, s2 })
 }
 
 
@@ -258,47 +496,383 @@ pub fn queryzk<T: Curve>(pk: &Pk<T>, set: &HashSet<T::Scalar>, phi: &Vec<T::Scal
 ///
 /// * `pk` - The public key
 /// * `commitment` - The polynomial commitment
-/// * `pi_sj` - Optional Schnorr proof for non-membership
 /// * `kj` - The queried element
-/// * `witness` - The witness
-/// * `phi_hat_kj` - Optional evaluation phi_hat(kj)
+/// * `membership_proof` - The proof returned by `queryzk`
 ///
 /// # Returns
 ///
-/// `true` if the prover is honest
-/// `false` if the prover is dishonest
+/// `Ok(())` if the prover is honest
 ///
-pub fn verifyzk<T: Curve>(pk: &Pk<T>, commitment: T::G1, pi_sj: Option<(T::G1, T::G1, T::G1, T::Scalar, T::Scalar)>,
-kj: T::Scalar, witness: T::G1, phi_hat_kj: Option<T::Scalar>) -> bool {
-
-    if phi_hat_kj.is_some() {
-        let phi_hat_kj= phi_hat_kj.expect("invalid state");
-        return verifyeval(pk, commitment, kj, T::scalar_from_literal(&0), phi_hat_kj, witness);
-    }
-    // always revealing phi_hat_kj allows us to use the verifyeval function above, such that the commiter cannot deny kj is in the set
-
-
-    if pi_sj.is_none() {
-        return false
-    } 
+/// # Errors
+///
+/// * `KzgError::ClaimedMembershipIsFalse` if a non-membership proof shows that kj is in the set
+/// * `KzgError::SchnorrFailed` if the Schnorr proof is invalid
+/// * `KzgError::PairingCheckFailed` if the witness does not match the commitment
+///
+pub fn verifyzk<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, kj: T::Scalar, membership_proof: &MembershipProof<T>) -> Result<(), KzgError> {
 
-    let (proof, n1, n2, s1, s2) = pi_sj.expect("invalid state");
+    let (witness, proof, n1, n2, s1, s2) = match *membership_proof {
+        // always revealing phi_hat_kj allows us to use the verifyeval function, such that the commiter cannot deny kj is in the set
+        MembershipProof::Member { witness, phi_hat_kj } => {
+            if !verifyeval(pk, commitment.0, kj, T::scalar_from_literal(&0), phi_hat_kj, witness) {
+                return Err(KzgError::PairingCheckFailed);
+            }
+            return Ok(());
+        },
+        MembershipProof::NonMember { witness, proof, n1, n2, s1, s2 } => (witness, proof, n1, n2, s1, s2)
+    };
 
     // commiter lied, phi(kj) is in their set
     if n1 == T::g1mul(&s1, &T::g1()) {
-		return false
+		return Err(KzgError::ClaimedMembershipIsFalse)
 	}
     
     // we require both proofs to be valid
     if !schnorr_verify::<T>(pk, proof, n1, n2, s1, s2) {
-    	return false 
+    	return Err(KzgError::SchnorrFailed)
     }
 
 
-    let left = T::pairing(&T::g1sub(&commitment, &proof), &T::g2());
+    let left = T::pairing(&T::g1sub(&commitment.0, &proof), &T::g2());
     let right = T::pairing(&witness, &T::g2sub(&pk.alpha_g2, &T::g2mul(&kj, &T::g2())));
     
-    left == right
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+/// Verifies many membership proofs against the same commitment at once
+///
+/// Every proof satisfies e(w, g2^α) = e(C · Y^-1 · w^kj, g2), where Y is h^phi_hat(kj)
+/// for members and the `proof` element for non-members.
+/// The equations are combined with random coefficients into a single multi-pairing,
+/// and the Schnorr proofs of the non-members are checked with a single msm.
+/// A batch is accepted exactly when every proof would be accepted by `verifyzk`,
+/// except with negligible probability.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commitzk`
+/// * `proofs` - The queried elements together with their proofs
+/// * `rng` - Cryptographically secure random number generator for the coefficients
+///
+/// # Returns
+///
+/// `Ok(())` if all proofs are valid
+///
+/// # Errors
+///
+/// * `KzgError::ClaimedMembershipIsFalse` if a non-membership proof shows that its element is in the set
+/// * `KzgError::SchnorrFailed` if a Schnorr proof is invalid
+/// * `KzgError::PairingCheckFailed` if a witness does not match the commitment
+///
+pub fn verify_batch<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, commitment: &Commitment<T>, proofs: &[(T::Scalar, MembershipProof<T>)], rng: &mut R) -> Result<(), KzgError> {
+    let zero = T::scalar_from_literal(&0);
+    let identity = T::g1mul(&zero, &T::g1());
+
+    // one random coefficient per proof, shared by the schnorr and the pairing check
+    let coefficients: Vec<T::Scalar> = proofs.iter().map(|_| T::random_scalar(rng)).collect();
+
+    // Σ ρ (n1 + n2 - s1·g - s2·h - c·z) = 0
+    let mut schnorr_scalars = Vec::new();
+    let mut schnorr_bases = Vec::new();
+    let mut s1_sum = zero;
+    let mut s2_sum = zero;
+
+    // Σ r·w and Σ r·(C - Y + kj·w)
+    let mut witnesses = Vec::with_capacity(proofs.len());
+    let mut commitment_coefficient = zero;
+    let mut h_coefficient = zero;
+    let mut right_scalars = Vec::new();
+    let mut right_bases = Vec::new();
+
+    for ((kj, membership_proof), r) in proofs.iter().zip(coefficients.iter()) {
+        let witness = membership_proof.witness();
+        witnesses.push(witness);
+        commitment_coefficient = commitment_coefficient + *r;
+        right_scalars.push(*r * *kj);
+        right_bases.push(witness);
+
+        match *membership_proof {
+            MembershipProof::Member { phi_hat_kj, .. } => {
+                h_coefficient = h_coefficient - *r * phi_hat_kj;
+            },
+            MembershipProof::NonMember { proof, n1, n2, s1, s2, .. } => {
+                // commiter lied, phi(kj) is in their set
+                if n1 == T::g1mul(&s1, &T::g1()) {
+                    return Err(KzgError::ClaimedMembershipIsFalse);
+                }
+
+                let c = T::fiat_shamir_hash(proof, n1, n2, pk.h1);
+                schnorr_scalars.extend([*r, *r, zero - *r * c]);
+                schnorr_bases.extend([n1, n2, proof]);
+                s1_sum = s1_sum + *r * s1;
+                s2_sum = s2_sum + *r * s2;
+
+                right_scalars.push(zero - *r);
+                right_bases.push(proof);
+            }
+        }
+    }
+
+    schnorr_scalars.extend([zero - s1_sum, zero - s2_sum]);
+    schnorr_bases.extend([T::g1(), pk.h1]);
+    if T::msm_g1(&schnorr_scalars, &schnorr_bases) != identity {
+        return Err(KzgError::SchnorrFailed);
+    }
+
+    right_scalars.extend([commitment_coefficient, h_coefficient]);
+    right_bases.extend([commitment.0, pk.h1]);
+
+    let left = T::msm_g1(&coefficients, &witnesses);
+    let right = T::msm_g1(&right_scalars, &right_bases);
+
+    // e(Σ r·w, g2^α) · e(-Σ r·(C - Y + kj·w), g2) = 1
+    let pairs = [(left, pk.alpha_g2), (T::g1sub(&identity, &right), T::g2())];
+    if T::multi_pairing(&pairs) != T::gt_one() {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+/// Generates a single membership proof for several elements of the set
+///
+/// The proof consists of one witness regardless of the number of elements.
+/// Like `queryzk` for members it reveals phi_hat(kj) for every queried element,
+/// here in the form of the remainder r_hat(x).
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `kjs` - The distinct elements to prove membership of
+///
+/// # Returns
+///
+/// A `BatchMembershipProof<T>` for all elements
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::ElementNotInSet` if one of the elements is not in the set or appears twice
+///
+pub fn query_members_batch<T: Curve>(pk: &Pk<T>, opening: &Opening<T>, kjs: &[T::Scalar]) -> Result<BatchMembershipProof<T>, KzgError> {
+    check_opening(opening)?;
+    let max = pk.g2_powers.len() - 1;
+    if kjs.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: kjs.len() });
+    }
+
+    let vanishing = poly::roots_polynomial::<T>(kjs);
+
+    let (quotient, remainder) = poly::divide::<T>(&opening.phi, &vanishing);
+    // φ has distinct roots, so it is divisible exactly when every kj is a distinct root
+    let zero = T::scalar_from_literal(&0);
+    if remainder.iter().any(|x| *x != zero) {
+        return Err(KzgError::ElementNotInSet);
+    }
+
+    let (hiding_quotient, hiding_remainder) = poly::divide::<T>(&opening.phi_hat, &vanishing);
+
+    let witness = commit_pair::<T>(pk, &quotient, &hiding_quotient)?;
+
+    Ok(BatchMembershipProof { witness, remainder: hiding_remainder })
+}
+
+
+/// Verifies a proof that several elements are in the committed set
+///
+/// Checks e(C · h^-r_hat(α), g2) = e(w, g2^Z(α)), where g2^Z(α) is computed from
+/// the powers of α in G2.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commitzk`
+/// * `kjs` - The queried elements, in any order
+/// * `proof` - The proof returned by `query_members_batch`
+///
+/// # Returns
+///
+/// `Ok(())` if every element is in the set
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::MalformedProof` if the remainder does not have one coefficient per element
+/// * `KzgError::PairingCheckFailed` if the witness does not match the commitment
+///
+pub fn verify_members_batch<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, kjs: &[T::Scalar], proof: &BatchMembershipProof<T>) -> Result<(), KzgError> {
+    let max = pk.g2_powers.len() - 1;
+    if kjs.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: kjs.len() });
+    }
+    if proof.remainder.len() != kjs.len() {
+        return Err(KzgError::MalformedProof);
+    }
+
+    let vanishing = poly::roots_polynomial::<T>(kjs);
+    let vanishing_g2 = commit_poly_g2::<T>(&vanishing, &pk.g2_powers)?;
+
+    let hidden_remainder = commit_poly::<T>(&proof.remainder, &pk.h_powers)?;
+
+    let left = T::pairing(&T::g1sub(&commitment.0, &hidden_remainder), &T::g2());
+    let right = T::pairing(&proof.witness, &vanishing_g2);
+
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+/// Generates a single non-membership proof for several elements
+///
+/// The proof consists of five points and three scalars, independent of the
+/// number of elements. The blinding by ρ and σ makes the revealed evaluations
+/// r'(z) and u'(z) uniformly random.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `kjs` - The distinct elements to prove non-membership of
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// A `BatchNonMembershipProof<T>` for all elements
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::ElementInSet` if one of the elements is in the set
+///
+pub fn query_non_members_batch<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening: &Opening<T>, kjs: &[T::Scalar], rng: &mut R) -> Result<BatchNonMembershipProof<T>, KzgError> {
+    check_opening(opening)?;
+    check_batch::<T>(pk, kjs)?;
+
+    let vanishing = poly::roots_polynomial::<T>(kjs);
+    // u·φ + b·Z = 1, so u is the inverse of r = φ mod Z
+    let (inverse, _) = poly::bezout::<T>(&opening.phi, &vanishing).ok_or(KzgError::ElementInSet)?;
+
+    let (quotient, remainder) = poly::divide::<T>(&opening.phi, &vanishing);
+    let (hiding_quotient, hiding_remainder) = poly::divide::<T>(&opening.phi_hat, &vanishing);
+
+    // r' = r + ρ·Z and u' = u + σ·Z are still inverse modulo Z, and φ = (q - ρ)·Z + r'
+    let zero = T::scalar_from_literal(&0);
+    let one = T::scalar_from_literal(&1);
+    let (rho, sigma) = (T::random_scalar(rng), T::random_scalar(rng));
+    let remainder = poly::subtract::<T>(&remainder, &poly::product::<T>(&vec![zero - rho], &vanishing));
+    let inverse = poly::subtract::<T>(&inverse, &poly::product::<T>(&vec![zero - sigma], &vanishing));
+    let quotient = poly::subtract::<T>(&quotient, &vec![rho]);
+
+    // r'·u' - 1 = t'·Z
+    let (cofactor, _) = poly::divide::<T>(&poly::subtract::<T>(&poly::product::<T>(&remainder, &inverse), &vec![one]), &vanishing);
+
+    let hiding_inverse: Vec<_> = (0..inverse.len()).map(|_| T::random_scalar(rng)).collect();
+    let hiding_cofactor: Vec<_> = (0..cofactor.len()).map(|_| T::random_scalar(rng)).collect();
+
+    let witness = commit_pair::<T>(pk, &quotient, &hiding_quotient)?;
+    let remainder_commitment = commit_pair::<T>(pk, &remainder, &hiding_remainder)?;
+    let inverse_commitment = commit_pair::<T>(pk, &inverse, &hiding_inverse)?;
+    let cofactor_commitment = commit_pair::<T>(pk, &cofactor, &hiding_cofactor)?;
+
+    // the challenge is bound to the commitment the proof is checked against
+    let commitment = commit_pair::<T>(pk, &opening.phi, &opening.phi_hat)?;
+    let z = transcript_challenge::<T>(b"kzg batch non-membership", kjs, &[pk.h1, commitment, witness, remainder_commitment, inverse_commitment, cofactor_commitment], &[]);
+    let remainder_z = apply::<T>(&remainder, &z);
+    let inverse_z = apply::<T>(&inverse, &z);
+    let gamma = transcript_challenge::<T>(b"kzg batch non-membership", &[z, remainder_z, inverse_z], &[], &[]);
+
+    // opens f = r' + γ·u' + γ²·t' and its hiding polynomial at z with the witness (f - f(z)) / (x - z)
+    let combine = |f: &Vec<T::Scalar>, g: &Vec<T::Scalar>, h: &Vec<T::Scalar>| {
+        let f = poly::subtract::<T>(f, &poly::product::<T>(&vec![zero - gamma], g));
+        poly::subtract::<T>(&f, &poly::product::<T>(&vec![zero - gamma * gamma], h))
+    };
+    let combined = combine(&remainder, &inverse, &cofactor);
+    let hiding_combined = combine(&hiding_remainder, &hiding_inverse, &hiding_cofactor);
+
+    let divisor = vec![one, zero - z];
+    let (opening_quotient, _) = poly::divide::<T>(&combined, &divisor);
+    let (hiding_opening_quotient, _) = poly::divide::<T>(&hiding_combined, &divisor);
+
+    Ok(BatchNonMembershipProof {
+        witness,
+        remainder: remainder_commitment,
+        inverse: inverse_commitment,
+        cofactor: cofactor_commitment,
+        opening: commit_pair::<T>(pk, &opening_quotient, &hiding_opening_quotient)?,
+        remainder_z,
+        inverse_z,
+        hiding_z: apply::<T>(&hiding_combined, &z)
+    })
+}
+
+
+/// Verifies a proof that several elements are not in the committed set
+///
+/// Checks e(C / R, g2) = e(w, g2^Z(α)), where R is the remainder commitment and
+/// g2^Z(α) is a single multi-scalar multiplication over the powers of α in G2.
+/// Then checks the opening of R · U^γ · T^γ² at z against r'(z) + γ·u'(z) + γ²·t'(z),
+/// with t'(z) = (r'(z)·u'(z) - 1) / Z(z). The work besides computing Z(x) does not
+/// depend on the number of elements.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commitzk`
+/// * `kjs` - The queried elements, in the order used by the prover
+/// * `proof` - The proof returned by `query_non_members_batch`
+///
+/// # Returns
+///
+/// `Ok(())` if no element is in the set
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::MalformedProof` if the challenge z is one of the queried elements
+/// * `KzgError::PairingCheckFailed` if the commitments do not match or the remainder is not invertible
+///
+pub fn verify_non_members_batch<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, kjs: &[T::Scalar], proof: &BatchNonMembershipProof<T>) -> Result<(), KzgError> {
+    check_batch::<T>(pk, kjs)?;
+
+    let vanishing = poly::roots_polynomial::<T>(kjs);
+    let vanishing_g2 = commit_poly_g2::<T>(&vanishing, &pk.g2_powers)?;
+
+    let left = T::pairing(&T::g1sub(&commitment.0, &proof.remainder), &T::g2());
+    let right = T::pairing(&proof.witness, &vanishing_g2);
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+
+    let z = transcript_challenge::<T>(b"kzg batch non-membership", kjs, &[pk.h1, commitment.0, proof.witness, proof.remainder, proof.inverse, proof.cofactor], &[]);
+    let gamma = transcript_challenge::<T>(b"kzg batch non-membership", &[z, proof.remainder_z, proof.inverse_z], &[], &[]);
+
+    // happens with negligible probability for an honest prover
+    let vanishing_z = apply::<T>(&vanishing, &z);
+    if vanishing_z == T::scalar_from_literal(&0) {
+        return Err(KzgError::MalformedProof);
+    }
+    let one = T::scalar_from_literal(&1);
+    let cofactor_z = (proof.remainder_z * proof.inverse_z - one) * T::scalar_inv(&vanishing_z);
+
+    let combined = T::msm_g1(&[one, gamma, gamma * gamma], &[proof.remainder, proof.inverse, proof.cofactor]);
+    let combined_z = proof.remainder_z + gamma * proof.inverse_z + gamma * gamma * cofactor_z;
+
+    if !verifyeval(pk, combined, z, combined_z, proof.hiding_z, proof.opening) {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
 }
 
 
@@ -315,12 +889,9 @@ fn verifyeval<T: Curve>(pk: &Pk<T>, commitment: T::G1, kj: T::Scalar, phi_kj: T:
     left == right
 }
 
-fn schnorr_proof<T: Curve>(pk: &Pk<T>, a: T::Scalar, b: T::Scalar, random: &mut Vec<u128>) -> (T::G1, T::G1, T::Scalar, T::Scalar) {
-    let r1 = random.pop().expect("not enough randomness provided");
-    let r2 = random.pop().expect("not enough randomness provided");
-	
-    let r1 = T::scalar_from_literal(&r1); 
-    let r2 = T::scalar_from_literal(&r2); 
+fn schnorr_proof<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, a: T::Scalar, b: T::Scalar, rng: &mut R) -> (T::G1, T::G1, T::Scalar, T::Scalar) {
+    let r1 = T::random_scalar(rng); 
+    let r2 = T::random_scalar(rng); 
 
     let n1 = T::g1mul(&r1, &T::g1());
     let n2 = T::g1mul(&r2, &pk.h1);
@@ -330,7 +901,7 @@ fn schnorr_proof<T: Curve>(pk: &Pk<T>, a: T::Scalar, b: T::Scalar, random: &mut
     
     let z = T::g1add(&z1, &z2);
 
-    let c = T::fiat_shamir_hash(z, n1, n2, pk.h1, T::g1());
+    let c = T::fiat_shamir_hash(z, n1, n2, pk.h1);
 
     let s1 = r1 - c * a; 
     let s2 = r2 - c * b; 
@@ -340,7 +911,7 @@ fn schnorr_proof<T: Curve>(pk: &Pk<T>, a: T::Scalar, b: T::Scalar, random: &mut
 
 fn schnorr_verify<T: Curve>(pk: &Pk<T>, z: T::G1, n1: T::G1, n2: T::G1, s1: T::Scalar, s2: T::Scalar) -> bool {
     
-    let c = T::fiat_shamir_hash(z, n1, n2, pk.h1, T::g1());
+    let c = T::fiat_shamir_hash(z, n1, n2, pk.h1);
 
     let left  = T::g1add(&n1, &n2);
 
@@ -355,18 +926,115 @@ fn schnorr_verify<T: Curve>(pk: &Pk<T>, z: T::G1, n1: T::G1, n2: T::G1, s1: T::S
     left == right
 }
 
-fn commit_poly<T: Curve>(polynomial: &Vec<T::Scalar> , pk: &Vec<T::G1>, generator: T::G1) -> T::G1 {
-    // commit to the original polynomial
-    let mut commitment = T::g1mul(&T::scalar_from_literal(&0), &generator);
-    
+fn commit_poly<T: Curve>(polynomial: &Vec<T::Scalar> , pk: &Vec<T::G1>) -> Result<T::G1, KzgError> {
+    if polynomial.len() > pk.len() {
+        return Err(KzgError::SetTooLarge { max: pk.len() - 1, got: polynomial.len() - 1 });
+    }
+
+    // the constant term belongs to the last power
     let difference = pk.len() - polynomial.len();
-    for i in 0..polynomial.len() { 
-        let power_index = difference + i;
-        
-        let current= T::g1mul(&polynomial[i], &pk[power_index]);
-        commitment = T::g1add(&commitment, &current);
-    };
-    commitment
+    Ok(parallel::msm_g1::<T>(polynomial, &pk[difference..]))
+}
+
+// commits to a polynomial in G2, the constant term belongs to the last power
+fn commit_poly_g2<T: Curve>(polynomial: &Vec<T::Scalar>, powers: &Vec<T::G2>) -> Result<T::G2, KzgError> {
+    if polynomial.len() > powers.len() {
+        return Err(KzgError::SetTooLarge { max: powers.len() - 1, got: polynomial.len() - 1 });
+    }
+
+    let difference = powers.len() - polynomial.len();
+    Ok(T::msm_g2(polynomial, &powers[difference..]))
+}
+
+// commits to a polynomial in G1 hidden by a second polynomial, g^f(α) · h^f_hat(α)
+fn commit_pair<T: Curve>(pk: &Pk<T>, polynomial: &Vec<T::Scalar>, hiding: &Vec<T::Scalar>) -> Result<T::G1, KzgError> {
+    Ok(T::g1add(&commit_poly::<T>(polynomial, &pk.g_powers)?, &commit_poly::<T>(hiding, &pk.h_powers)?))
+}
+
+// hides φ with a random polynomial of the same degree and commits to both
+fn commit_hiding<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, phi: Vec<T::Scalar>, rng: &mut R) -> Result<(Commitment<T>, Opening<T>), KzgError> {
+    let mut phi_hat = vec![T::scalar_from_literal(&0); phi.len()];
+    
+    // create a random hiding polynomial
+    for i in 0..phi_hat.len() {
+        phi_hat[i] = T::random_scalar(rng);
+    }
+    
+    let (commitment, hiding_commitment) = parallel::join(
+        || commit_poly::<T>(&phi, &pk.g_powers),
+        || commit_poly::<T>(&phi_hat, &pk.h_powers)
+    );
+    let (commitment, hiding_commitment) = (commitment?, hiding_commitment?);
+
+    
+    Ok((Commitment(T::g1add(&commitment, &hiding_commitment)), Opening { phi, phi_hat }))
+}
+
+// checks that φ and phi_hat of an opening are not empty and have the same length
+pub(crate) fn check_opening<T: Curve>(opening: &Opening<T>) -> Result<(), KzgError> {
+    if opening.phi.is_empty() || opening.phi.len() != opening.phi_hat.len() {
+        return Err(KzgError::MalformedOpening);
+    }
+    Ok(())
+}
+
+// checks that a batch fits the public key and has no repeated elements
+fn check_batch<T: Curve>(pk: &Pk<T>, kjs: &[T::Scalar]) -> Result<(), KzgError> {
+    let max = pk.g2_powers.len() - 1;
+    if kjs.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: kjs.len() });
+    }
+
+    let distinct: HashSet<&T::Scalar> = kjs.iter().collect();
+    if distinct.len() != kjs.len() {
+        return Err(KzgError::DuplicateElement);
+    }
+    Ok(())
+}
+
+// a Fiat-Shamir challenge from a domain separating label and the messages of a proof
+// hashes the canonical encodings, so both curve implementations agree
+pub(crate) fn transcript_challenge<T: Curve>(label: &[u8], scalars: &[T::Scalar], points: &[T::G1], g2_points: &[T::G2]) -> T::Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(label);
+
+    for x in scalars {
+        hasher.update(T::scalar_to_bytes(x));
+    }
+    for point in points {
+        hasher.update(T::g1_to_bytes(point));
+    }
+    for point in g2_points {
+        hasher.update(T::g2_to_bytes(point));
+    }
+
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    T::scalar_from_bytes_wide(&bytes)
+}
+
+// commits to R = h^r(α) and proves knowledge of the coefficients of r with respect to the
+// powers of h, since the prover does not know r(α) itself
+// the challenge is computed from R and the nonce h^k(α), the response is k(x) - c·r(x)
+pub(crate) fn prove_hidden_polynomial<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, polynomial: &Vec<T::Scalar>, challenge: impl FnOnce(T::G1, T::G1) -> T::Scalar, rng: &mut R) -> Result<(T::G1, T::G1, Vec<T::Scalar>), KzgError> {
+    let commitment = commit_poly::<T>(polynomial, &pk.h_powers)?;
+
+    let k: Vec<_> = (0..polynomial.len()).map(|_| T::random_scalar(rng)).collect();
+    let nonce = commit_poly::<T>(&k, &pk.h_powers)?;
+    let c = challenge(commitment, nonce);
+
+    let response = k.iter().zip(polynomial.iter()).map(|(k, r)| *k - c * *r).collect();
+    Ok((commitment, nonce, response))
+}
+
+// checks a proof of `prove_hidden_polynomial`, h^s(α) · R^c = h^k(α)
+pub(crate) fn verify_hidden_polynomial<T: Curve>(pk: &Pk<T>, commitment: T::G1, nonce: T::G1, response: &Vec<T::Scalar>, challenge: impl FnOnce(T::G1, T::G1) -> T::Scalar) -> Result<(), KzgError> {
+    let c = challenge(commitment, nonce);
+    let hidden_response = commit_poly::<T>(response, &pk.h_powers)?;
+    if T::g1add(&hidden_response, &T::g1mul(&c, &commitment)) != nonce {
+        return Err(KzgError::SchnorrFailed);
+    }
+    Ok(())
 }
 
 // applies the polynomial to input x
@@ -419,7 +1087,7 @@ fn create_psi<T: Curve>(f: &Vec<T::Scalar>, f_x0: T::Scalar, x0: T::Scalar) -> V
 
 //creates the witness g^psi(i)h^psi_hat(i)
 fn create_witness<T: Curve>(phi: &Vec<T::Scalar>, phi_hat: &Vec<T::Scalar>, i: T::Scalar, pk: &Pk<T>) 
--> (T::Scalar, T::Scalar, T::Scalar, T::G1) {
+-> Result<(T::Scalar, T::Scalar, T::Scalar, T::G1), KzgError> {
 
     let phi_i  = apply::<T>(&phi, &i);
     let phi_hat_i = apply::<T>(&phi_hat, &i);
@@ -428,10 +1096,10 @@ fn create_witness<T: Curve>(phi: &Vec<T::Scalar>, phi_hat: &Vec<T::Scalar>, i: T
     let psi_hat = create_psi::<T>(&phi_hat, phi_hat_i, i);
 
         
-    let mut witness = commit_poly::<T>(&psi, &pk.g_powers, T::g1());
-    witness = T::g1add(&witness, &commit_poly::<T>(&psi_hat, &pk.h_powers, pk.h1));
+    let mut witness = commit_poly::<T>(&psi, &pk.g_powers)?;
+    witness = T::g1add(&witness, &commit_poly::<T>(&psi_hat, &pk.h_powers)?);
 
-    return (i, phi_i, phi_hat_i, witness);
+    Ok((i, phi_i, phi_hat_i, witness))
 }
 
 
@@ -548,32 +1216,16 @@ fn schnorr_verify_verifiable(pk: &PkVerifiable, z: G1, n1: G1, n2: G1, s1: Scala
 mod tests {
     use quickcheck_macros::quickcheck;
     use quickcheck::TestResult;
-    use rand::random;
     use super::*;
+    use tape::{generate_randomness, random_scalars, setup_tape, Tape};
 
-    // Generate random numbers for testing purposes
-    // Production code should use cryptographic randomness
-    // to maintain security properties
-    fn generate_randomness(n: usize) -> Vec<u128> {
-        let mut rand: Vec<u128> = Vec::with_capacity(n); 
-        for _ in 0..n {
-            let mut r = random();
-            while r == 0 {
-                r = random();
-            }
-            rand.push(r)
-        }
-        rand
-    }
-
-    
     // this tests of the completeness standard schnorr proof
     #[quickcheck]
     fn test_schnorr_verification(a: u128, b: u128) -> bool {
         use curve::SpecCurve as Curve;
         // use curve::FastCurve as Curve;
 
-        let mut random = generate_randomness(5);
+        let mut random = Tape::new(generate_randomness(4 * 4));
         
         let pk: Pk<Curve> = setup(1, &mut random);
 
@@ -600,7 +1252,7 @@ mod tests {
         use curve::SpecCurve as Curve;
         // use curve::FastCurve as Curve;
 
-		let mut random = generate_randomness(20);
+		let mut random = Tape::new(generate_randomness(20));
 
 		let pk: Pk<Curve> = setup(1, &mut random);
 
@@ -613,7 +1265,7 @@ mod tests {
 		let proof = Curve::g1add(&p1, &p2);
 
 		// fake knowledge of a by simulating guessing it
-		let a = Curve::scalar_from_literal(&random.pop().expect("not enough randomness provided"));
+		let a = Curve::scalar_from_literal(&generate_randomness(1)[0]);
 
 			
 		let (n1, n2, s1, s2) = schnorr_proof(&pk, a, b, &mut random);
@@ -628,7 +1280,7 @@ mod tests {
 		use curve::SpecCurve as Curve;
         // use curve::FastCurve as Curve;
 
-		let mut random = generate_randomness(20);
+		let mut random = Tape::new(generate_randomness(20));
 
 		let pk: Pk<Curve> = setup(1, &mut random);
 
@@ -655,7 +1307,7 @@ mod tests {
 		use curve::SpecCurve as Curve;
         // use curve::FastCurve as Curve;
 
-		let mut random = generate_randomness(20);
+		let mut random = Tape::new(generate_randomness(20));
 
 		let pk: Pk<Curve> = setup(1, &mut random);
 
@@ -700,19 +1352,23 @@ mod tests {
             kj = Curve::scalar_from_literal(&random.pop().expect("not enough randomness provided"));
         }
  
-        let mut random = generate_randomness(degree + 5);
+        let mut random = Tape::new(generate_randomness(4 * (degree + 5)));
         
 
         let pk: Pk<Curve> = setup(degree as u128, &mut random);
         
 
-        let (commitment, phi, phi_hat) = commitzk(&pk, &set, &mut random);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
         
-        let (kj, witness, phi_hat_kj, pi_sj) = queryzk(&pk, &set, &phi, &phi_hat, kj, &mut random);
+        let membership_proof = queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key");
+
+        if membership_proof.is_member() != set.contains(&kj) {
+            return false;
+        }
 
-        let result = verifyzk(&pk, commitment, pi_sj, kj, witness, phi_hat_kj);
+        let result = verifyzk(&pk, &commitment, kj, &membership_proof);
         
-        return result;
+        return result.is_ok();
     }
 
     
@@ -739,15 +1395,15 @@ mod tests {
             kj = Curve::scalar_from_literal(&random.pop().expect("not enough randomness provided"));
         }
  
-        let mut random = generate_randomness(degree + 5);
+        let mut random = Tape::new(generate_randomness(4 * (degree + 5)));
         
 
         let pk: Pk<Curve> = setup(degree as u128, &mut random);
         
 
-        let (commitment, phi, phi_hat) = commitzk(&pk, &set, &mut random); 
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key"); 
     
-        let (kj, _witness, phi_hat_kj, pi_sj) = queryzk(&pk, &set, &phi, &phi_hat, kj, &mut random);
+        let membership_proof = queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key");
         
         
         let mut random = generate_randomness(degree);
@@ -759,10 +1415,15 @@ mod tests {
         
         let witness = Curve::g1mul(&apply::<Curve>(&forged_poly, &kj), &Curve::g1());
 
+        let forged_proof = match membership_proof {
+            MembershipProof::Member { phi_hat_kj, .. } => MembershipProof::Member { witness, phi_hat_kj },
+            MembershipProof::NonMember { proof, n1, n2, s1, s2, .. } => MembershipProof::NonMember { witness, proof, n1, n2, s1, s2 }
+        };
 
-        let result = verifyzk(&pk, commitment, pi_sj, kj, witness, phi_hat_kj);
 
-        return ! result
+        let result = verifyzk(&pk, &commitment, kj, &forged_proof);
+
+        return result.is_err()
     }
 	
     
@@ -789,20 +1450,20 @@ mod tests {
             set.insert(kj);
         }
  
-        let mut random = generate_randomness(degree + 5);
+        let mut random = Tape::new(generate_randomness(4 * (degree + 5)));
         
 
         let pk: Pk<Curve> = setup(degree as u128, &mut random);
         
 
-        let (commitment, phi, phi_hat) = commitzk(&pk, &set, &mut random); 
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key"); 
     
-        let (kj, witness, _phi_hat_kj, _pi_sj) = queryzk(&pk, &set, &phi, &phi_hat, kj, &mut random);
+        let witness = queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key").witness();
 		
 
         // creating this part of the schnorr proof manually
-		let phi_kj =  apply::<Curve>(&phi, &kj);
-		let phi_hat_kj =  apply::<Curve>(&phi_hat, &kj);
+		let phi_kj =  apply::<Curve>(&opening.phi, &kj);
+		let phi_hat_kj =  apply::<Curve>(&opening.phi_hat, &kj);
 
 		let p1 = Curve::g1mul(&phi_kj, &Curve::g1());
 		let p2 = Curve::g1mul(&phi_hat_kj, &pk.h1);
@@ -810,13 +1471,13 @@ mod tests {
 
 
 		let (n1, n2, s1, s2) = schnorr_proof(&pk, phi_kj, phi_hat_kj, &mut random);
-		let pi = (proof, n1, n2, s1, s2);
+		let pi = MembershipProof::NonMember { witness, proof, n1, n2, s1, s2 };
 
 
         // falsely claiming that phi(kj) != 0
-        let result = verifyzk(&pk, commitment, Some(pi), kj, witness, None);
+        let result = verifyzk(&pk, &commitment, kj, &pi);
 
-        return ! result
+        return result.is_err()
     }
     
 
@@ -847,20 +1508,245 @@ mod tests {
         kj = Curve::scalar_from_literal(&random.pop().expect("not enough randomness provided"));
  
 
-        let mut random = generate_randomness(degree + 5);
+        let mut random = Tape::new(generate_randomness(4 * (degree + 5)));
         
 
         let pk: Pk<Curve> = setup(degree as u128, &mut random);
         
 
-        let (commitment, phi, phi_hat) = commitzk(&pk, &set, &mut random); 
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key"); 
     
-        let (kj, witness, phi_hat_kj, _pi_sj) = queryzk(&pk, &set, &phi, &phi_hat, kj, &mut random);
+        let witness = queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key").witness();
+		let phi_hat_kj =  apply::<Curve>(&opening.phi_hat, &kj);
 
         // falsely claiming that phi(kj) == 0
-        let result = verifyzk(&pk, commitment, None, kj, witness, phi_hat_kj);
+        let pi = MembershipProof::Member { witness, phi_hat_kj };
+        let result = verifyzk(&pk, &commitment, kj, &pi);
+
+        return result == Err(KzgError::PairingCheckFailed)
+    }
+
+
+    // sets and openings that do not fit the public key are rejected instead of panicking
+    #[test]
+    fn test_kzg_set_too_large() {
+        use curve::FastCurve as Curve;
+
+        let mut random = Tape::new(generate_randomness(4 * 16));
+        let pk: Pk<Curve> = setup(2, &mut random);
+
+        let set: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let result = commitzk(&pk, &set, &mut random);
+        assert_eq!(result.err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+
+        let small_pk: Pk<Curve> = setup(1, &mut random);
+        let set: HashSet<_> = [1, 2].iter().map(Curve::scalar_from_literal).collect();
+        let (_, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+        let result = queryzk(&small_pk, &opening, Curve::scalar_from_literal(&1), &mut random);
+        assert_eq!(result.err(), Some(KzgError::SetTooLarge { max: 1, got: 2 }));
+
+        let malformed = Opening::<Curve> { phi: opening.phi.clone(), phi_hat: Vec::new() };
+        let result = queryzk(&pk, &malformed, Curve::scalar_from_literal(&1), &mut random);
+        assert_eq!(result.err(), Some(KzgError::MalformedOpening));
+    }
+
+
+    // debug output does not leak the set
+    #[test]
+    fn test_opening_debug() {
+        use curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(2, &mut rng);
+        let set: HashSet<_> = [123456789].iter().map(Curve::scalar_from_literal).collect();
+        let (_, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+
+        assert_eq!(format!("{:?}", opening), "Opening { .. }");
+    }
+
+
+    // this tests completeness and soundness of batch verification
+    #[quickcheck]
+    fn test_kzg_verify_batch(queries: Vec<bool>, tamper: usize) -> bool {
+        use curve::FastCurve as Curve;
+
+        let degree = 6;
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(degree);
+        let members: Vec<_> = set.iter().copied().collect();
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let mut rng = rand::rng();
+        let mut proofs = Vec::new();
+        for (i, is_in_set) in queries.iter().take(8).enumerate() {
+            let kj = if *is_in_set {
+                members[i % members.len()]
+            } else {
+                Curve::scalar_from_literal(&generate_randomness(1)[0])
+            };
+            proofs.push((kj, queryzk(&pk, &opening, kj, &mut rng).expect("opening fits the public key")));
+        }
+
+        if verify_batch(&pk, &commitment, &proofs, &mut rng).is_err() {
+            return false;
+        }
+        if proofs.is_empty() {
+            return true;
+        }
+
+        // claiming the proof for a different element
+        let index = tamper % proofs.len();
+        proofs[index].0 = proofs[index].0 + Curve::scalar_from_literal(&1);
+
+        verify_batch(&pk, &commitment, &proofs, &mut rng).is_err()
+    }
+
+
+    // this tests completeness and soundness of the multi-point opening
+    #[quickcheck]
+    fn test_kzg_members_batch(count: usize) -> bool {
+        use curve::FastCurve as Curve;
+
+        let degree = 8;
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(degree);
+        let kjs: Vec<_> = set.iter().copied().take(count % degree).collect();
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let proof = query_members_batch(&pk, &opening, &kjs).expect("elements are in the set");
+        if verify_members_batch(&pk, &commitment, &kjs, &proof).is_err() {
+            return false;
+        }
+
+        // elements that are not in the set cannot be proven
+        let non_member = Curve::scalar_from_literal(&generate_randomness(1)[0]);
+        let mut with_non_member = kjs.clone();
+        with_non_member.push(non_member);
+        if query_members_batch(&pk, &opening, &with_non_member).err() != Some(KzgError::ElementNotInSet) {
+            return false;
+        }
+
+        // the proof does not verify for other elements
+        if kjs.is_empty() {
+            return true;
+        }
+        let mut other = kjs.clone();
+        other[0] = non_member;
+        verify_members_batch(&pk, &commitment, &other, &proof) == Err(KzgError::PairingCheckFailed)
+    }
+
+
+    // this tests completeness and soundness of batched non-membership proofs
+    #[quickcheck]
+    fn test_kzg_non_members_batch(count: usize) -> bool {
+        use curve::FastCurve as Curve;
+
+        let degree = 8;
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(degree);
+        // not in the set with overwhelming probability
+        let kjs: Vec<_> = random_scalars::<Curve, _>(count % degree);
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let mut rng = rand::rng();
+        let proof = query_non_members_batch(&pk, &opening, &kjs, &mut rng).expect("elements are not in the set");
+        if verify_non_members_batch(&pk, &commitment, &kjs, &proof).is_err() {
+            return false;
+        }
+
+        // members cannot be denied
+        let member = *set.iter().next().expect("set is not empty");
+        let mut with_member = kjs.clone();
+        with_member.push(member);
+        if query_non_members_batch(&pk, &opening, &with_member, &mut rng).err() != Some(KzgError::ElementInSet) {
+            return false;
+        }
+
+        if kjs.is_empty() {
+            return true;
+        }
+
+        // the proof does not verify for other elements
+        let mut other = kjs.clone();
+        other[0] = member;
+        if verify_non_members_batch(&pk, &commitment, &other, &proof).is_ok() {
+            return false;
+        }
+
+        // the remainder is bound to the commitment
+        let mut forged = proof.clone();
+        forged.remainder = Curve::g1add(&forged.remainder, &Curve::g1());
+        if verify_non_members_batch(&pk, &commitment, &kjs, &forged) != Err(KzgError::PairingCheckFailed) {
+            return false;
+        }
+
+        // and the claimed evaluations to the opening
+        let mut forged = proof.clone();
+        forged.inverse_z = forged.inverse_z + Curve::scalar_from_literal(&1);
+        verify_non_members_batch(&pk, &commitment, &kjs, &forged) == Err(KzgError::PairingCheckFailed)
+    }
+
+
+    // this tests completeness and soundness of the public key check
+    #[quickcheck]
+    fn test_pk_verify(degree: u8, index: usize) -> bool {
+        use curve::FastCurve as Curve;
+
+        let degree = degree % 8 + 1;
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(degree as u128, &mut rng);
+        if pk.verify().is_err() {
+            return false;
+        }
+        let index = index % degree as usize;
+
+        let forge = |change: &dyn Fn(&mut Pk<Curve>)| {
+            let mut forged = Pk { g_powers: pk.g_powers.clone(), h_powers: pk.h_powers.clone(), g2_powers: pk.g2_powers.clone(), h1: pk.h1, alpha_g2: pk.alpha_g2 };
+            change(&mut forged);
+            forged.verify()
+        };
+
+        // a power that does not step by α, in each of the three chains
+        let g_power = forge(&|pk| pk.g_powers[index] = Curve::g1add(&pk.g_powers[index], &Curve::g1()));
+        let h_power = forge(&|pk| pk.h_powers[index] = Curve::g1add(&pk.h_powers[index], &Curve::g1()));
+        let g2_power = forge(&|pk| pk.g2_powers[index] = Curve::g2add(&pk.g2_powers[index], &Curve::g2()));
+
+        // hiding powers of a different α with the same h
+        let other: Pk<Curve> = setup(degree as u128, &mut rng);
+        let h_alpha = forge(&|pk| pk.h_powers = other.h_powers.clone());
+
+        let identity = Curve::g1mul(&Curve::scalar_from_literal(&0), &Curve::g1());
+        let h_identity = forge(&|pk| {
+            pk.h1 = identity;
+            pk.h_powers = vec![identity; pk.h_powers.len()];
+        });
+        let truncated = forge(&|pk| { pk.h_powers.pop(); });
+
+        [g_power, h_power, g2_power, h_alpha].iter().all(|result| *result == Err(KzgError::PairingCheckFailed))
+            && h_identity == Err(KzgError::InvalidEncoding)
+            && truncated == Err(KzgError::InvalidEncoding)
+    }
+
+    // points outside of the prime order subgroup are rejected before the pairings
+    #[test]
+    fn test_pk_verify_subgroup() {
+        use curve::SpecCurve as Curve;
+
+        let mut rng = rand::rng();
+        let mut pk: Pk<Curve> = setup(1, &mut rng);
+        assert!(pk.verify().is_ok());
 
-        return ! result
+        // (0, 2) is on the curve and has order 3
+        let torsion = (Fp::from_literal(0), Fp::from_literal(2), false);
+        pk.h1 = torsion;
+        pk.h_powers[1] = torsion;
+        assert_eq!(pk.verify(), Err(KzgError::InvalidEncoding));
     }
 	
 }
@@ -0,0 +1,236 @@
+//! # Multisets
+//!
+//! Commitments to multisets and proofs of the multiplicity of an element.
+//!
+//! A multiset is committed as φ(x) = ∏(x - s)^m(s), hidden by phi_hat(x) like a set.
+//! To prove that kj occurs exactly m times the prover divides out (x - kj)^m,
+//! φ = q·(x - kj)^m and phi_hat = q_hat·(x - kj)^m + r_hat, and shows that
+//! * the commitment opens to the quotient, e(C · h^-r_hat(α), g2) = e(w, g2^((α - kj)^m))
+//! * the quotient is non-zero at kj, with a non-membership proof for kj against w = g^q(α) · h^q_hat(α)
+//!
+//! The existing membership proofs work unchanged on multiset commitments.
+//!
+//! ```
+//! use kzg::{setup, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::multiset::{commit_multiset, query_multiplicity, verify_multiplicity};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let elements: Vec<_> = [1, 2, 2].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (commitment, opening) = commit_multiset(&pk, &elements, &mut rng).expect("multiset fits the public key");
+//!
+//! let kj = FastCurve::scalar_from_literal(&2);
+//! let proof = query_multiplicity(&pk, &opening, kj, 2, &mut rng).expect("2 occurs twice");
+//! assert!(verify_multiplicity(&pk, &commitment, kj, 2, &proof).is_ok());
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{check_opening, commit_hiding, commit_pair, commit_poly, commit_poly_g2, poly, queryzk, verifyzk};
+use crate::{Commitment, KzgError, MembershipProof, Opening, Pk};
+
+
+/// Proof that an element occurs exactly m times in the multiset
+///
+/// # Fields
+///
+/// * `witness` - The quotient commitment w = g^q(α) · h^q_hat(α)
+/// * `remainder` - The remainder r_hat(x), m coefficients
+/// * `quotient_proof` - A non-membership proof of kj against the quotient commitment w
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiplicityProof<T: Curve> {
+    pub witness: T::G1,
+    pub remainder: Vec<T::Scalar>,
+    pub quotient_proof: MembershipProof<T>
+}
+
+
+/// Creates a zero-knowledge commitment to a multiset
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `elements` - The elements of the multiset, repeated according to their multiplicity
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// The commitment C = g^φ(α) · h^phi_hat(α) and the opening, like `commitzk`
+///
+/// # Errors
+///
+/// `KzgError::SetTooLarge` if there are more elements than the degree of the public key
+///
+pub fn commit_multiset<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, elements: &[T::Scalar], rng: &mut R) -> Result<(Commitment<T>, Opening<T>), KzgError> {
+    let max = pk.g_powers.len() - 1;
+    if elements.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: elements.len() });
+    }
+
+    let phi = poly::roots_polynomial::<T>(elements);
+
+    commit_hiding(pk, phi, rng)
+}
+
+
+/// Generates a proof that kj occurs exactly m times
+///
+/// A multiplicity of zero proves that kj is not in the multiset.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commit_multiset`
+/// * `kj` - The queried element
+/// * `multiplicity` - The claimed multiplicity m
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// A `MultiplicityProof<T>` for kj
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if the multiplicity is larger than the degree of the public key
+/// * `KzgError::WrongMultiplicity` if kj does not occur exactly m times
+///
+pub fn query_multiplicity<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening: &Opening<T>, kj: T::Scalar, multiplicity: usize, rng: &mut R) -> Result<MultiplicityProof<T>, KzgError> {
+    check_opening(opening)?;
+    let max = pk.g2_powers.len() - 1;
+    if multiplicity > max {
+        return Err(KzgError::SetTooLarge { max, got: multiplicity });
+    }
+
+    let divisor = poly::roots_polynomial::<T>(&vec![kj; multiplicity]);
+
+    // kj occurs at least m times
+    let zero = T::scalar_from_literal(&0);
+    let (quotient, remainder) = poly::divide::<T>(&opening.phi, &divisor);
+    if quotient.is_empty() || remainder.iter().any(|x| *x != zero) {
+        return Err(KzgError::WrongMultiplicity);
+    }
+
+    let (hiding_quotient, hiding_remainder) = poly::divide::<T>(&opening.phi_hat, &divisor);
+
+    // kj occurs at most m times
+    let quotient_opening = Opening { phi: quotient, phi_hat: hiding_quotient };
+    let quotient_proof = queryzk(pk, &quotient_opening, kj, rng)?;
+    if quotient_proof.is_member() {
+        return Err(KzgError::WrongMultiplicity);
+    }
+
+    let witness = commit_pair::<T>(pk, &quotient_opening.phi, &quotient_opening.phi_hat)?;
+
+    Ok(MultiplicityProof { witness, remainder: hiding_remainder, quotient_proof })
+}
+
+
+/// Verifies a proof that kj occurs exactly m times
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commit_multiset`
+/// * `kj` - The queried element
+/// * `multiplicity` - The claimed multiplicity m
+/// * `proof` - The proof returned by `query_multiplicity`
+///
+/// # Returns
+///
+/// `Ok(())` if kj occurs exactly m times
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if the multiplicity is larger than the degree of the public key
+/// * `KzgError::MalformedProof` if the remainder does not have m coefficients or the quotient proof is a membership proof
+/// * `KzgError::PairingCheckFailed` if the witness does not match the commitment
+/// * any error of `verifyzk` for the quotient proof
+///
+pub fn verify_multiplicity<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, kj: T::Scalar, multiplicity: usize, proof: &MultiplicityProof<T>) -> Result<(), KzgError> {
+    let max = pk.g2_powers.len() - 1;
+    if multiplicity > max {
+        return Err(KzgError::SetTooLarge { max, got: multiplicity });
+    }
+    if proof.remainder.len() != multiplicity || proof.quotient_proof.is_member() {
+        return Err(KzgError::MalformedProof);
+    }
+
+    let divisor = poly::roots_polynomial::<T>(&vec![kj; multiplicity]);
+    let divisor_g2 = commit_poly_g2::<T>(&divisor, &pk.g2_powers)?;
+    let hidden_remainder = commit_poly::<T>(&proof.remainder, &pk.h_powers)?;
+
+    let left = T::pairing(&T::g1sub(&commitment.0, &hidden_remainder), &T::g2());
+    let right = T::pairing(&proof.witness, &divisor_g2);
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+
+    verifyzk(pk, &Commitment(proof.witness), kj, &proof.quotient_proof)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use rand::random;
+    use crate::tape::{random_scalars, setup_tape};
+    use super::*;
+
+    // this tests completeness and soundness of multiplicity proofs
+    #[quickcheck]
+    fn test_multiplicity(multiplicity: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let degree = 8;
+        let multiplicity = (multiplicity % 4) as usize;
+
+        let kj = Curve::scalar_from_literal(&random());
+        let mut elements: Vec<_> = random_scalars::<Curve, _>(3);
+        elements.extend(vec![kj; multiplicity]);
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commit_multiset(&pk, &elements, &mut random).expect("multiset fits the public key");
+
+        let mut rng = rand::rng();
+        let proof = query_multiplicity(&pk, &opening, kj, multiplicity, &mut rng).expect("kj has the multiplicity");
+        if verify_multiplicity(&pk, &commitment, kj, multiplicity, &proof).is_err() {
+            return false;
+        }
+
+        // the prover cannot claim a different multiplicity
+        if query_multiplicity(&pk, &opening, kj, multiplicity + 1, &mut rng).err() != Some(KzgError::WrongMultiplicity) {
+            return false;
+        }
+        if multiplicity > 0 && query_multiplicity(&pk, &opening, kj, multiplicity - 1, &mut rng).err() != Some(KzgError::WrongMultiplicity) {
+            return false;
+        }
+
+        // nor reuse the proof for another multiplicity
+        let mut shorter = proof.clone();
+        shorter.remainder.pop();
+        multiplicity == 0 || verify_multiplicity(&pk, &commitment, kj, multiplicity - 1, &shorter).is_err()
+    }
+
+    // membership proofs work on multiset commitments
+    #[test]
+    fn test_multiset_membership() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = crate::setup(4, &mut rng);
+
+        let elements: Vec<_> = [5, 5, 5, 7].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment, opening) = commit_multiset(&pk, &elements, &mut rng).expect("multiset fits the public key");
+
+        let five = Curve::scalar_from_literal(&5);
+        let proof = queryzk(&pk, &opening, five, &mut rng).expect("opening fits the public key");
+        assert!(proof.is_member());
+        assert!(verifyzk(&pk, &commitment, five, &proof).is_ok());
+
+        let elements: Vec<_> = [1, 2, 3, 4, 5].iter().map(Curve::scalar_from_literal).collect();
+        assert_eq!(commit_multiset(&pk, &elements, &mut rng).err(), Some(KzgError::SetTooLarge { max: 4, got: 5 }));
+    }
+}
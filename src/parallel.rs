@@ -0,0 +1,193 @@
+//! # Parallelism
+//!
+//! The expensive loops of the setup and of committing, each in a sequential version and,
+//! with the `parallel` feature, a version that splits the work across rayon threads.
+//! Both versions compute the same values, so callers do not need to know which one is used.
+//!
+//! With the feature the powers of a fixed base are computed with a windowed table: every
+//! 4 bit window of the scalar selects a precomputed multiple j · 2^(4w) · base, so a
+//! multiplication costs 64 additions instead of 255 doublings and about 128 additions.
+//! The scalars are the secret powers of α, so the entry is selected with `Curve::g1_lookup`
+//! and `Curve::g2_lookup`, which read the whole row of the table whatever the window is.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use crate::curve::Curve;
+#[cfg(feature = "parallel")]
+use crate::curve::SCALAR_BYTES;
+
+// below this many scalars building the table costs more than it saves
+#[cfg(feature = "parallel")]
+const FIXED_BASE_THRESHOLD: usize = 32;
+
+// smallest part of a multi scalar multiplication given to a thread
+#[cfg(feature = "parallel")]
+const MSM_CHUNK: usize = 256;
+
+
+// bits of a window of the fixed base tables
+#[cfg(feature = "parallel")]
+const WINDOW_BITS: usize = 4;
+
+// windows of a scalar
+#[cfg(feature = "parallel")]
+const WINDOWS: usize = 8 * SCALAR_BYTES / WINDOW_BITS;
+
+
+// base · s for every scalar s
+#[cfg(feature = "parallel")]
+pub(crate) fn batch_mul_g1<T: Curve>(base: &T::G1, scalars: &[T::Scalar]) -> Vec<T::G1> {
+    if scalars.len() < FIXED_BASE_THRESHOLD {
+        return scalars.par_iter().map(|s| T::g1mul(s, base)).collect();
+    }
+    let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+    let scalars: Vec<_> = scalars.par_iter().map(T::scalar_to_bytes).collect();
+    fixed_base(base, &scalars, identity, T::g1add, T::g1_lookup)
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn batch_mul_g1<T: Curve>(base: &T::G1, scalars: &[T::Scalar]) -> Vec<T::G1> {
+    scalars.iter().map(|s| T::g1mul(s, base)).collect()
+}
+
+
+// base · s for every scalar s in G2
+#[cfg(feature = "parallel")]
+pub(crate) fn batch_mul_g2<T: Curve>(base: &T::G2, scalars: &[T::Scalar]) -> Vec<T::G2> {
+    if scalars.len() < FIXED_BASE_THRESHOLD {
+        return scalars.par_iter().map(|s| T::g2mul(s, base)).collect();
+    }
+    let identity = T::g2mul(&T::scalar_from_literal(&0), &T::g2());
+    let scalars: Vec<_> = scalars.par_iter().map(T::scalar_to_bytes).collect();
+    fixed_base(base, &scalars, identity, T::g2add, T::g2_lookup)
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn batch_mul_g2<T: Curve>(base: &T::G2, scalars: &[T::Scalar]) -> Vec<T::G2> {
+    scalars.iter().map(|s| T::g2mul(s, base)).collect()
+}
+
+
+// base · s for big endian scalars in any group, given by its identity, addition and table lookup
+#[cfg(feature = "parallel")]
+fn fixed_base<P, A, L>(base: &P, scalars: &[[u8; SCALAR_BYTES]], identity: P, add: A, lookup: L) -> Vec<P>
+where P: Copy + Send + Sync, A: Fn(&P, &P) -> P + Sync, L: Fn(&[P], usize) -> P + Sync {
+    // 2^(4w) · base for the windows w = 0, ..., 63
+    let mut window_bases = Vec::with_capacity(WINDOWS);
+    let mut window_base = *base;
+    for _ in 0..WINDOWS {
+        window_bases.push(window_base);
+        for _ in 0..WINDOW_BITS {
+            window_base = add(&window_base, &window_base);
+        }
+    }
+
+    // tables[w][j] = j · 2^(4w) · base
+    let tables: Vec<Vec<P>> = window_bases.par_iter().map(|window_base| {
+        let mut table = Vec::with_capacity(1 << WINDOW_BITS);
+        let mut multiple = identity;
+        for _ in 0..(1 << WINDOW_BITS) {
+            table.push(multiple);
+            multiple = add(&multiple, window_base);
+        }
+        table
+    }).collect();
+
+    scalars.par_iter().map(|bytes| {
+        tables.iter().enumerate().fold(identity, |result, (w, table)| {
+            // the encoding is big endian, so the last byte holds the two lowest windows
+            let byte = bytes[SCALAR_BYTES - 1 - w / 2];
+            let digit = (byte >> (WINDOW_BITS * (w % 2))) & 0x0f;
+            add(&result, &lookup(table, digit as usize))
+        })
+    }).collect()
+}
+
+
+// Σ scalars[i] · bases[i], one chunk per thread
+#[cfg(feature = "parallel")]
+pub(crate) fn msm_g1<T: Curve>(scalars: &[T::Scalar], bases: &[T::G1]) -> T::G1 {
+    assert_eq!(scalars.len(), bases.len(), "msm needs one scalar per base");
+    let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+
+    let chunk = scalars.len().div_ceil(rayon::current_num_threads()).max(MSM_CHUNK);
+    scalars.par_chunks(chunk).zip(bases.par_chunks(chunk))
+        .map(|(scalars, bases)| T::msm_g1(scalars, bases))
+        .reduce(|| identity, |x, y| T::g1add(&x, &y))
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn msm_g1<T: Curve>(scalars: &[T::Scalar], bases: &[T::G1]) -> T::G1 {
+    T::msm_g1(scalars, bases)
+}
+
+
+// combines neighbouring values, a last value without a neighbour is kept
+#[cfg(feature = "parallel")]
+pub(crate) fn pairwise<V, F>(values: &[V], combine: F) -> Vec<V>
+where V: Clone + Send + Sync, F: Fn(&V, &V) -> V + Sync {
+    values.par_chunks(2).map(|pair| match pair {
+        [x, y] => combine(x, y),
+        [x] => x.clone(),
+        _ => unreachable!("chunks have one or two elements")
+    }).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn pairwise<V, F>(values: &[V], combine: F) -> Vec<V>
+where V: Clone + Send + Sync, F: Fn(&V, &V) -> V + Sync {
+    values.chunks(2).map(|pair| match pair {
+        [x, y] => combine(x, y),
+        [x] => x.clone(),
+        _ => unreachable!("chunks have one or two elements")
+    }).collect()
+}
+
+
+// runs two independent computations, on two threads with the feature
+#[cfg(feature = "parallel")]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send {
+    rayon::join(a, b)
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send {
+    (a(), b())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use super::*;
+
+    // both versions agree with multiplying one scalar at a time
+    #[quickcheck]
+    fn test_batch_mul(scalars: Vec<u128>, base: u128) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        // enough scalars for the windowed table, the inverses fill all of its windows
+        let scalars: Vec<_> = scalars.iter().chain(&[u128::MAX; 40]).map(|x| Curve::scalar_inv(&Curve::scalar_from_literal(x))).collect();
+        let base = Curve::scalar_from_literal(&base);
+        let g1 = Curve::g1mul(&base, &Curve::g1());
+        let g2 = Curve::g2mul(&base, &Curve::g2());
+
+        [&scalars[..], &scalars[..3]].iter().all(|scalars| {
+            batch_mul_g1::<Curve>(&g1, scalars) == scalars.iter().map(|s| Curve::g1mul(s, &g1)).collect::<Vec<_>>()
+                && batch_mul_g2::<Curve>(&g2, scalars) == scalars.iter().map(|s| Curve::g2mul(s, &g2)).collect::<Vec<_>>()
+        })
+    }
+
+    #[quickcheck]
+    fn test_msm(scalars: Vec<u128>) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        // more than one chunk
+        let scalars: Vec<_> = scalars.iter().cycle().take(600).map(Curve::scalar_from_literal).collect();
+        let bases: Vec<_> = (0..scalars.len() as u128).map(|i| Curve::g1mul(&Curve::scalar_from_literal(&(i + 1)), &Curve::g1())).collect();
+
+        msm_g1::<Curve>(&scalars, &bases) == Curve::msm_g1(&scalars, &bases)
+    }
+}
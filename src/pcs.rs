@@ -0,0 +1,268 @@
+//! # Polynomial Commitments
+//!
+//! The hiding KZG polynomial commitment scheme behind the set commitments, for arbitrary polynomials.
+//!
+//! A polynomial f(x) of degree at most d is committed as C = g^f(α) · h^f_hat(α) with a random
+//! blinding polynomial f_hat(x) of the same degree. An opening at z reveals y = f(z) and
+//! y_hat = f_hat(z) together with the witness w = g^ψ(α) · h^psi_hat(α) for the quotients
+//! ψ = (f - y) / (x - z) and psi_hat = (f_hat - y_hat) / (x - z), which the verifier checks with
+//! e(w, g2^(α - z)) = e(C · g^-y · h^-y_hat, g2).
+//!
+//! Public data does not need hiding. `commit_public`, `open_public` and `verify_public`
+//! drop the blinding polynomial and commit to C = g^f(α) only, the standard 48 byte KZG
+//! commitment with 48 byte evaluation proofs w = g^ψ(α). Both modes use the same `setup` output.
+//!
+//! Polynomials are stored highest degree first, like everywhere else in the crate.
+//!
+//! ```
+//! use kzg::{setup, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::pcs::{commit, open, verify};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! // f(x) = x^2 + 2x + 3
+//! let f: Vec<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (commitment, blinding) = commit(&pk, &f, &mut rng).expect("polynomial fits the public key");
+//!
+//! let z = FastCurve::scalar_from_literal(&2);
+//! let (y, y_hat, proof) = open(&pk, &f, &blinding, z).expect("polynomial fits the public key");
+//! assert_eq!(y, FastCurve::scalar_from_literal(&11));
+//! assert!(verify(&pk, &commitment, z, y, y_hat, proof).is_ok());
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{apply, commit_hiding, commit_poly, create_psi, create_witness, verifyeval, Commitment, KzgError, Pk};
+
+
+/// Commits to a polynomial with a random blinding polynomial
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `polynomial` - The coefficients of f(x), highest degree first
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// The commitment C = g^f(α) · h^f_hat(α) and the blinding polynomial f_hat(x)
+///
+/// # Errors
+///
+/// `KzgError::SetTooLarge` if the degree of the polynomial is larger than the degree of the public key
+///
+pub fn commit<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, polynomial: &[T::Scalar], rng: &mut R) -> Result<(Commitment<T>, Vec<T::Scalar>), KzgError> {
+    let (commitment, opening) = commit_hiding(pk, polynomial.to_vec(), rng)?;
+    Ok((commitment, opening.phi_hat))
+}
+
+
+/// Opens a committed polynomial at a point
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `polynomial` - The committed polynomial f(x)
+/// * `blinding` - The blinding polynomial f_hat(x) returned by `commit`
+/// * `z` - The evaluation point
+///
+/// # Returns
+///
+/// The evaluations y = f(z) and y_hat = f_hat(z) and the witness for them
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if the polynomials are empty or differ in length
+/// * `KzgError::SetTooLarge` if the degree of the polynomial is larger than the degree of the public key
+///
+pub fn open<T: Curve>(pk: &Pk<T>, polynomial: &[T::Scalar], blinding: &[T::Scalar], z: T::Scalar) -> Result<(T::Scalar, T::Scalar, T::G1), KzgError> {
+    if polynomial.is_empty() || polynomial.len() != blinding.len() {
+        return Err(KzgError::MalformedOpening);
+    }
+    if polynomial.len() > pk.g_powers.len() {
+        return Err(KzgError::SetTooLarge { max: pk.g_powers.len() - 1, got: polynomial.len() - 1 });
+    }
+
+    let (_, y, y_hat, witness) = create_witness(&polynomial.to_vec(), &blinding.to_vec(), z, pk)?;
+    Ok((y, y_hat, witness))
+}
+
+
+/// Verifies an opening of a committed polynomial
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commit`
+/// * `z` - The evaluation point
+/// * `y` - The claimed evaluation f(z)
+/// * `y_hat` - The evaluation of the blinding polynomial f_hat(z)
+/// * `proof` - The witness returned by `open`
+///
+/// # Returns
+///
+/// `Ok(())` if the committed polynomial evaluates to y at z
+///
+/// # Errors
+///
+/// `KzgError::PairingCheckFailed` if the witness does not match the commitment and the evaluations
+///
+pub fn verify<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, z: T::Scalar, y: T::Scalar, y_hat: T::Scalar, proof: T::G1) -> Result<(), KzgError> {
+    if !verifyeval(pk, commitment.0, z, y, y_hat, proof) {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+/// Commits to a polynomial without blinding
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `polynomial` - The coefficients of f(x), highest degree first
+///
+/// # Returns
+///
+/// The commitment C = g^f(α)
+///
+/// # Errors
+///
+/// `KzgError::SetTooLarge` if the degree of the polynomial is larger than the degree of the public key
+///
+pub fn commit_public<T: Curve>(pk: &Pk<T>, polynomial: &[T::Scalar]) -> Result<Commitment<T>, KzgError> {
+    Ok(Commitment(commit_poly::<T>(&polynomial.to_vec(), &pk.g_powers)?))
+}
+
+
+/// Opens a polynomial committed with `commit_public` at a point
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `polynomial` - The committed polynomial f(x)
+/// * `z` - The evaluation point
+///
+/// # Returns
+///
+/// The evaluation y = f(z) and the witness w = g^ψ(α)
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if the polynomial is empty
+/// * `KzgError::SetTooLarge` if the degree of the polynomial is larger than the degree of the public key
+///
+pub fn open_public<T: Curve>(pk: &Pk<T>, polynomial: &[T::Scalar], z: T::Scalar) -> Result<(T::Scalar, T::G1), KzgError> {
+    if polynomial.is_empty() {
+        return Err(KzgError::MalformedOpening);
+    }
+    if polynomial.len() > pk.g_powers.len() {
+        return Err(KzgError::SetTooLarge { max: pk.g_powers.len() - 1, got: polynomial.len() - 1 });
+    }
+
+    let polynomial = polynomial.to_vec();
+    let y = apply::<T>(&polynomial, &z);
+    let psi = create_psi::<T>(&polynomial, y, z);
+
+    Ok((y, commit_poly::<T>(&psi, &pk.g_powers)?))
+}
+
+
+/// Verifies an opening of a polynomial committed with `commit_public`
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commit_public`
+/// * `z` - The evaluation point
+/// * `y` - The claimed evaluation f(z)
+/// * `proof` - The witness returned by `open_public`
+///
+/// # Returns
+///
+/// `Ok(())` if the committed polynomial evaluates to y at z
+///
+/// # Errors
+///
+/// `KzgError::PairingCheckFailed` if the witness does not match the commitment and the evaluation
+///
+pub fn verify_public<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, z: T::Scalar, y: T::Scalar, proof: T::G1) -> Result<(), KzgError> {
+    // without blinding the hiding part of the check vanishes
+    verify(pk, commitment, z, y, T::scalar_from_literal(&0), proof)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::setup;
+    use super::*;
+
+    // this tests completeness and soundness of openings
+    #[quickcheck]
+    fn test_open(polynomial: Vec<u128>, z: u128) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(8, &mut rng);
+
+        let polynomial: Vec<_> = polynomial.iter().take(9).map(Curve::scalar_from_literal).collect();
+        if polynomial.is_empty() {
+            return open(&pk, &polynomial, &polynomial, Curve::scalar_from_literal(&z)).err() == Some(KzgError::MalformedOpening);
+        }
+
+        let (commitment, blinding) = commit(&pk, &polynomial, &mut rng).expect("polynomial fits the public key");
+        let z = Curve::scalar_from_literal(&z);
+        let (y, y_hat, proof) = open(&pk, &polynomial, &blinding, z).expect("polynomial fits the public key");
+
+        let one = Curve::scalar_from_literal(&1);
+        y == apply::<Curve>(&polynomial, &z)
+            && verify(&pk, &commitment, z, y, y_hat, proof).is_ok()
+            && verify(&pk, &commitment, z, y + one, y_hat, proof) == Err(KzgError::PairingCheckFailed)
+            && verify(&pk, &commitment, z, y, y_hat + one, proof) == Err(KzgError::PairingCheckFailed)
+    }
+
+    // the public mode shares the setup with the hiding mode
+    #[quickcheck]
+    fn test_open_public(polynomial: Vec<u128>, z: u128) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(8, &mut rng);
+
+        let polynomial: Vec<_> = polynomial.iter().take(9).map(Curve::scalar_from_literal).collect();
+        if polynomial.is_empty() {
+            return open_public(&pk, &polynomial, Curve::scalar_from_literal(&z)).err() == Some(KzgError::MalformedOpening);
+        }
+
+        let commitment = commit_public(&pk, &polynomial).expect("polynomial fits the public key");
+        let z = Curve::scalar_from_literal(&z);
+        let (y, proof) = open_public(&pk, &polynomial, z).expect("polynomial fits the public key");
+
+        // a hiding opening with a zero blinding polynomial is a public opening
+        let zero = vec![Curve::scalar_from_literal(&0); polynomial.len()];
+        let (_, _, hiding_proof) = open(&pk, &polynomial, &zero, z).expect("polynomial fits the public key");
+
+        let one = Curve::scalar_from_literal(&1);
+        y == apply::<Curve>(&polynomial, &z)
+            && proof == hiding_proof
+            && verify_public(&pk, &commitment, z, y, proof).is_ok()
+            && verify_public(&pk, &commitment, z, y + one, proof) == Err(KzgError::PairingCheckFailed)
+    }
+
+    #[test]
+    fn test_polynomial_too_large() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(2, &mut rng);
+
+        let polynomial: Vec<_> = [1, 2, 3, 4].iter().map(Curve::scalar_from_literal).collect();
+        assert_eq!(commit(&pk, &polynomial, &mut rng).err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+        assert_eq!(open(&pk, &polynomial, &polynomial, Curve::scalar_from_literal(&1)).err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+        assert_eq!(commit_public(&pk, &polynomial).err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+        assert_eq!(open_public(&pk, &polynomial, Curve::scalar_from_literal(&1)).err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+    }
+}
@@ -0,0 +1,465 @@
+//! # Polynomial Arithmetic
+//!
+//! Fast construction of the set polynomial φ(x) = ∏(x - s).
+//!
+//! Multiplying the linear factors one after another is quadratic in the size of the set.
+//! Instead the factors are multiplied pairwise in a subproduct tree, where every level
+//! multiplies polynomials with the number theoretic transform (NTT) over the
+//! 2^32-th roots of unity of the BLS12-381 scalar field.
+//! This takes O(n log^2 n) field operations.
+//!
+//! Like everywhere else in the crate, polynomials are stored highest degree first.
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use kzg::poly::set_polynomial;
+//! use kzg::curve::{Curve, FastCurve};
+//!
+//! let set: HashSet<_> = [1, 2].iter().map(FastCurve::scalar_from_literal).collect();
+//! let phi = set_polynomial::<FastCurve>(&set);
+//!
+//! // (x - 1)(x - 2) = x^2 - 3x + 2
+//! let zero = FastCurve::scalar_from_literal(&0);
+//! let expected: Vec<_> = [1, 3, 2].iter().map(FastCurve::scalar_from_literal).collect();
+//! assert_eq!(phi, vec![expected[0], zero - expected[1], expected[2]]);
+//! ```
+
+use std::collections::HashSet;
+use crate::curve::Curve;
+use crate::{multiply, parallel};
+
+// 7 is a generator of the multiplicative group, 7^((r - 1) / 2^32) has order 2^32
+// the exponent (r - 1) / 2^32 split into two 128 bit halves
+const ROOT_EXP_HIGH: u128 = 0x73eda753299d7d483339d808;
+const ROOT_EXP_LOW: u128 = 0x09a1d80553bda402fffe5bfeffffffff;
+const MAX_LOG_SIZE: u32 = 32;
+
+// below this length schoolbook multiplication is faster than the transform
+const NTT_THRESHOLD: usize = 64;
+
+
+/// Computes the set polynomial φ(x) = ∏(x - s) for all s in the set
+///
+/// # Arguments
+///
+/// * `set` - The roots of the polynomial
+///
+/// # Returns
+///
+/// The monic polynomial of degree `set.len()`, highest degree first
+///
+pub fn set_polynomial<T: Curve>(set: &HashSet<T::Scalar>) -> Vec<T::Scalar> {
+    let roots: Vec<T::Scalar> = set.iter().copied().collect();
+    roots_polynomial::<T>(&roots)
+}
+
+
+// multiplies the linear factors (x - s) in a subproduct tree
+// roots may repeat, every occurrence is a factor
+pub(crate) fn roots_polynomial<T: Curve>(roots: &[T::Scalar]) -> Vec<T::Scalar> {
+    let one = T::scalar_from_literal(&1);
+    let zero = T::scalar_from_literal(&0);
+
+    if roots.is_empty() {
+        return vec![one];
+    }
+
+    let ntt = Ntt::<T>::new();
+
+    let mut layer: Vec<Vec<T::Scalar>> = roots.iter().map(|s| vec![one, zero - *s]).collect();
+    while layer.len() > 1 {
+        // the products of a level are independent
+        layer = parallel::pairwise(&layer, |f, g| ntt.multiply(f, g));
+    }
+
+    layer.pop().expect("layer is not empty")
+}
+
+
+// long division f = q·g + r with deg r < deg g
+// the remainder always has deg g coefficients, g must have a non-zero leading coefficient
+pub(crate) fn divide<T: Curve>(f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> (Vec<T::Scalar>, Vec<T::Scalar>) {
+    let zero = T::scalar_from_literal(&0);
+    let remainder_len = g.len() - 1;
+
+    if f.len() < g.len() {
+        let mut remainder = vec![zero; remainder_len - f.len()];
+        remainder.extend_from_slice(f);
+        return (Vec::new(), remainder);
+    }
+
+    let lead_inv = T::scalar_inv(&g[0]);
+    let mut remainder = f.clone();
+    let mut quotient = Vec::with_capacity(f.len() - remainder_len);
+
+    for i in 0..f.len() - remainder_len {
+        let coefficient = remainder[i] * lead_inv;
+        for (j, y) in g.iter().enumerate() {
+            remainder[i + j] = remainder[i + j] - coefficient * *y;
+        }
+        quotient.push(coefficient);
+    }
+
+    (quotient, remainder.split_off(f.len() - remainder_len))
+}
+
+
+// a primitive root of unity of order 2^log_order
+pub(crate) fn root_of_unity<T: Curve>(log_order: u32) -> T::Scalar {
+    assert!(log_order <= MAX_LOG_SIZE, "the scalar field has no roots of unity of this order");
+    T::scalar_pow(&Ntt::<T>::new().root, &(1 << (MAX_LOG_SIZE - log_order)))
+}
+
+// g^L_i(α) for the Lagrange polynomials of the roots of unity of order n, computed from
+// g^(α^0), ..., g^(α^(n-1)) with an inverse transform over the group
+pub(crate) fn lagrange_g1<T: Curve>(powers: &[T::G1]) -> Vec<T::G1> {
+    let n = powers.len();
+    assert!(n.is_power_of_two(), "the domain size has to be a power of two");
+    let log_size = n.trailing_zeros();
+
+    // the inverse transform uses omega^-1 = omega^(n - 1) and scales by 1/n
+    let omega = root_of_unity::<T>(log_size);
+    let mut values = powers.to_vec();
+    transform_g1::<T>(&mut values, T::scalar_pow(&omega, &(n as u128 - 1)));
+
+    let size_inv = T::scalar_inv(&T::scalar_from_literal(&(n as u128)));
+    values.iter().map(|x| T::g1mul(&size_inv, x)).collect()
+}
+
+// inverts all values with a single inversion, the values have to be non-zero
+pub(crate) fn batch_inverse<T: Curve>(values: &mut [T::Scalar]) {
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut product = T::scalar_from_literal(&1);
+    for value in values.iter() {
+        prefix.push(product);
+        product = product * *value;
+    }
+
+    let mut inverse = T::scalar_inv(&product);
+    for (value, prefix) in values.iter_mut().zip(prefix).rev() {
+        let next = inverse * *value;
+        *value = inverse * prefix;
+        inverse = next;
+    }
+}
+
+// Bézout coefficients a·f + b·g = 1 with deg a < deg g and deg b < deg f
+// None if f and g have a common root
+pub(crate) fn bezout<T: Curve>(f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> Option<(Vec<T::Scalar>, Vec<T::Scalar>)> {
+    let (gcd, a, b) = extended_gcd::<T>(f, g);
+    if gcd.len() != 1 {
+        return None;
+    }
+    Some((a, b))
+}
+
+// the monic gcd of f and g with a·f + b·g = gcd from the extended Euclidean algorithm
+// f and g must have non-zero leading coefficients
+pub(crate) fn extended_gcd<T: Curve>(f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> (Vec<T::Scalar>, Vec<T::Scalar>, Vec<T::Scalar>) {
+    let one = T::scalar_from_literal(&1);
+
+    // invariant: r = s·f + t·g for both rows, the zero polynomial is empty
+    let (mut r0, mut r1) = (f.clone(), g.clone());
+    let (mut s0, mut s1) = (vec![one], Vec::new());
+    let (mut t0, mut t1) = (Vec::new(), vec![one]);
+
+    while !r1.is_empty() {
+        let (q, r) = divide::<T>(&r0, &r1);
+        let r2 = trim::<T>(r);
+        let s2 = subtract::<T>(&s0, &product::<T>(&q, &s1));
+        let t2 = subtract::<T>(&t0, &product::<T>(&q, &t1));
+
+        (r0, r1) = (r1, r2);
+        (s0, s1) = (s1, s2);
+        (t0, t1) = (t1, t2);
+    }
+
+    let inverse = T::scalar_inv(&r0[0]);
+    let normalize = |p: Vec<T::Scalar>| p.iter().map(|x| *x * inverse).collect();
+    (normalize(r0), normalize(s0), normalize(t0))
+}
+
+// f - g, aligned at the constant term and without leading zeros
+pub(crate) fn subtract<T: Curve>(f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> Vec<T::Scalar> {
+    let zero = T::scalar_from_literal(&0);
+    let length = f.len().max(g.len());
+
+    let mut result = vec![zero; length - f.len()];
+    result.extend_from_slice(f);
+    for (x, y) in result[length - g.len()..].iter_mut().zip(g.iter()) {
+        *x = *x - *y;
+    }
+    trim::<T>(result)
+}
+
+// f·g, where either polynomial may be the empty zero polynomial
+pub(crate) fn product<T: Curve>(f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> Vec<T::Scalar> {
+    if f.is_empty() || g.is_empty() {
+        return Vec::new();
+    }
+    multiply::<T>(f, g)
+}
+
+// removes leading zero coefficients
+fn trim<T: Curve>(mut f: Vec<T::Scalar>) -> Vec<T::Scalar> {
+    let zero = T::scalar_from_literal(&0);
+    let leading = f.iter().take_while(|x| **x == zero).count();
+    f.drain(..leading);
+    f
+}
+
+// the roots of unity needed to multiply polynomials of any supported size
+struct Ntt<T: Curve> {
+    // primitive 2^32-th root of unity
+    root: T::Scalar,
+    // 1/2, used to compute 1/n for the inverse transform
+    half: T::Scalar
+}
+
+impl<T: Curve> Ntt<T> {
+    fn new() -> Self {
+        let seven = T::scalar_from_literal(&7);
+        // 7^(high * 2^128 + low)
+        let shifted = T::scalar_pow(&T::scalar_pow(&seven, &(1 << 64)), &(1 << 64));
+        let root = T::scalar_pow(&shifted, &ROOT_EXP_HIGH) * T::scalar_pow(&seven, &ROOT_EXP_LOW);
+
+        let half = T::scalar_inv(&T::scalar_from_literal(&2));
+
+        Ntt { root, half }
+    }
+
+    // multiplies two polynomials stored highest degree first
+    fn multiply(&self, f: &Vec<T::Scalar>, g: &Vec<T::Scalar>) -> Vec<T::Scalar> {
+        if f.len().min(g.len()) < NTT_THRESHOLD {
+            return multiply::<T>(f, g);
+        }
+
+        let length = f.len() + g.len() - 1;
+        let log_size = length.next_power_of_two().trailing_zeros();
+        assert!(log_size <= MAX_LOG_SIZE, "polynomial is too large for the 2^32 roots of unity");
+        let size = 1 << log_size;
+
+        // the transform works on coefficients lowest degree first
+        let zero = T::scalar_from_literal(&0);
+        let mut a: Vec<T::Scalar> = f.iter().rev().copied().collect();
+        let mut b: Vec<T::Scalar> = g.iter().rev().copied().collect();
+        a.resize(size, zero);
+        b.resize(size, zero);
+
+        // primitive root of unity of order size
+        let omega = T::scalar_pow(&self.root, &(1 << (MAX_LOG_SIZE - log_size)));
+        parallel::join(|| transform::<T>(&mut a, omega), || transform::<T>(&mut b, omega));
+
+        for (x, y) in a.iter_mut().zip(b.iter()) {
+            *x = *x * *y;
+        }
+
+        // the inverse transform uses omega^-1 = omega^(size - 1) and scales by 1/size
+        transform::<T>(&mut a, T::scalar_pow(&omega, &(size as u128 - 1)));
+        let size_inv = T::scalar_pow(&self.half, &(log_size as u128));
+
+        a.truncate(length);
+        a.iter().rev().map(|x| *x * size_inv).collect()
+    }
+}
+
+
+// i with its lowest `bits` bits in reverse order
+// bits is 0 for a single value, where shifting by usize::BITS would overflow
+pub(crate) fn reverse_bits(i: usize, bits: u32) -> usize {
+    i.reverse_bits().checked_shr(usize::BITS - bits).unwrap_or(0)
+}
+
+// in place iterative radix-2 transform, evaluates the polynomial at omega^0, ..., omega^(n-1)
+// the length of values has to be a power of two and omega a root of unity of that order
+fn transform<T: Curve>(values: &mut [T::Scalar], omega: T::Scalar) {
+    let n = values.len();
+
+    // bit reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = reverse_bits(i, bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let one = T::scalar_from_literal(&1);
+    let mut half = 1;
+    while half < n {
+        // primitive root of unity of order 2 * half
+        let step = T::scalar_pow(&omega, &((n / (2 * half)) as u128));
+
+        let mut twiddles = Vec::with_capacity(half);
+        let mut twiddle = one;
+        for _ in 0..half {
+            twiddles.push(twiddle);
+            twiddle = twiddle * step;
+        }
+
+        for block in values.chunks_mut(2 * half) {
+            let (low, high) = block.split_at_mut(half);
+            for ((x, y), w) in low.iter_mut().zip(high.iter_mut()).zip(twiddles.iter()) {
+                let t = *y * *w;
+                *y = *x - t;
+                *x = *x + t;
+            }
+        }
+
+        half *= 2;
+    }
+}
+
+
+// the same transform with group elements in place of coefficients
+fn transform_g1<T: Curve>(values: &mut [T::G1], omega: T::Scalar) {
+    let n = values.len();
+
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = reverse_bits(i, bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let one = T::scalar_from_literal(&1);
+    let mut half = 1;
+    while half < n {
+        let step = T::scalar_pow(&omega, &((n / (2 * half)) as u128));
+
+        let mut twiddles = Vec::with_capacity(half);
+        let mut twiddle = one;
+        for _ in 0..half {
+            twiddles.push(twiddle);
+            twiddle = twiddle * step;
+        }
+
+        for block in values.chunks_mut(2 * half) {
+            let (low, high) = block.split_at_mut(half);
+            for ((x, y), w) in low.iter_mut().zip(high.iter_mut()).zip(twiddles.iter()) {
+                let t = T::g1mul(w, y);
+                *y = T::g1sub(x, &t);
+                *x = T::g1add(x, &t);
+            }
+        }
+
+        half *= 2;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use crate::curve::{FastCurve, SpecCurve};
+    use crate::apply;
+    use super::*;
+
+    // the transform agrees with schoolbook multiplication
+    #[quickcheck]
+    fn test_ntt_multiply(f: Vec<u128>, g: Vec<u128>) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        // make sure the transform is used
+        let f: Vec<_> = f.iter().chain([1; NTT_THRESHOLD].iter()).map(Curve::scalar_from_literal).collect();
+        let g: Vec<_> = g.iter().chain([2; NTT_THRESHOLD].iter()).map(Curve::scalar_from_literal).collect();
+
+        Ntt::<Curve>::new().multiply(&f, &g) == multiply::<Curve>(&f, &g)
+    }
+
+    #[quickcheck]
+    fn test_set_polynomial(set: HashSet<u128>) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let set: HashSet<_> = set.iter().map(Curve::scalar_from_literal).collect();
+        let phi = set_polynomial::<Curve>(&set);
+
+        let zero = Curve::scalar_from_literal(&0);
+        phi.len() == set.len() + 1
+            && phi[0] == Curve::scalar_from_literal(&1)
+            && set.iter().all(|s| apply::<Curve>(&phi, s) == zero)
+    }
+
+    #[quickcheck]
+    fn test_divide(f: Vec<u128>, g: Vec<u128>, x: u128) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let f: Vec<_> = f.iter().map(Curve::scalar_from_literal).collect();
+        // a divisor of degree at least one
+        let g: Vec<_> = [2].iter().chain(g.iter()).chain([3].iter()).map(Curve::scalar_from_literal).collect();
+
+        let (q, r) = divide::<Curve>(&f, &g);
+
+        // f(x) = q(x)·g(x) + r(x)
+        let x = Curve::scalar_from_literal(&x);
+        r.len() == g.len() - 1
+            && apply::<Curve>(&f, &x) == apply::<Curve>(&q, &x) * apply::<Curve>(&g, &x) + apply::<Curve>(&r, &x)
+    }
+
+    #[quickcheck]
+    fn test_bezout(f: HashSet<u128>, g: HashSet<u128>, x: u128) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let f: Vec<_> = f.iter().map(Curve::scalar_from_literal).collect();
+        let g: Vec<_> = g.iter().map(Curve::scalar_from_literal).collect();
+        let (f_poly, g_poly) = (roots_polynomial::<Curve>(&f), roots_polynomial::<Curve>(&g));
+
+        match bezout::<Curve>(&f_poly, &g_poly) {
+            // a(x)·f(x) + b(x)·g(x) = 1
+            Some((a, b)) => {
+                let x = Curve::scalar_from_literal(&x);
+                a.len() <= g_poly.len()
+                    && b.len() <= f_poly.len()
+                    && apply::<Curve>(&a, &x) * apply::<Curve>(&f_poly, &x) + apply::<Curve>(&b, &x) * apply::<Curve>(&g_poly, &x) == Curve::scalar_from_literal(&1)
+            },
+            None => f.iter().any(|s| g.contains(s))
+        }
+    }
+
+    #[quickcheck]
+    fn test_extended_gcd(common: HashSet<u128>, f: HashSet<u128>, g: HashSet<u128>) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        // f and g share exactly the roots in common
+        let f_only: HashSet<_> = f.difference(&g).filter(|x| !common.contains(x)).collect();
+        let g_only: HashSet<_> = g.difference(&f).filter(|x| !common.contains(x)).collect();
+        let f: Vec<_> = common.iter().chain(f_only).map(Curve::scalar_from_literal).collect();
+        let g: Vec<_> = common.iter().chain(g_only).map(Curve::scalar_from_literal).collect();
+        let common: Vec<_> = common.iter().map(Curve::scalar_from_literal).collect();
+
+        let (gcd, _, _) = extended_gcd::<Curve>(&roots_polynomial::<Curve>(&f), &roots_polynomial::<Curve>(&g));
+        gcd == roots_polynomial::<Curve>(&common)
+    }
+
+    // large enough for several levels of the tree to use the transform
+    #[test]
+    fn test_set_polynomial_large() {
+        use crate::curve::FastCurve as Curve;
+
+        let roots: Vec<_> = (1..=300).map(|x| Curve::scalar_from_literal(&x)).collect();
+
+        let zero = Curve::scalar_from_literal(&0);
+        let mut expected = vec![Curve::scalar_from_literal(&1)];
+        for s in roots.iter() {
+            expected = multiply::<Curve>(&expected, &vec![Curve::scalar_from_literal(&1), zero - *s]);
+        }
+
+        assert_eq!(roots_polynomial::<Curve>(&roots), expected);
+    }
+
+    // both implementations compute the same root of unity
+    #[test]
+    fn test_root_of_unity() {
+        let spec = Ntt::<SpecCurve>::new().root;
+        let fast = Ntt::<FastCurve>::new().root;
+
+        assert_eq!(SpecCurve::scalar_to_bytes(&spec), FastCurve::scalar_to_bytes(&fast));
+        assert_eq!(fast, <blstrs::Scalar as group::ff::PrimeField>::ROOT_OF_UNITY);
+
+        // the root has order exactly 2^32
+        let one = FastCurve::scalar_from_literal(&1);
+        assert_ne!(FastCurve::scalar_pow(&fast, &(1 << 31)), one);
+        assert_eq!(FastCurve::scalar_pow(&fast, &(1 << 32)), one);
+    }
+}
@@ -0,0 +1,407 @@
+//! # Powers of Tau Transcripts
+//!
+//! Imports the public key from the `.ptau` transcript of a snarkjs powers of tau ceremony
+//! over BLS12-381, so that nobody who took part in the ceremony honestly knows α.
+//!
+//! The transcript is a list of sections, each a 4 byte type and an 8 byte size (little endian)
+//! followed by the data. The header section holds the size of a base field element, the field
+//! modulus and the power p of the ceremony. The `tauG1`, `tauG2` and `betaTauG1` sections
+//! hold g^(τ^i), g2^(τ^i) and (g^β)^(τ^i) as uncompressed points with little endian
+//! coordinates in Montgomery form.
+//!
+//! The hiding powers h^(α^i) cannot be computed for a new generator h without knowing α, so
+//! they have to come from the ceremony as well. `betaTauG1` is exactly that: the powers of τ
+//! applied to h = g^β, where β is a toxic secret of the ceremony just like τ, so nobody knows
+//! log_g(h) either. The Ethereum KZG ceremony only produced powers of g and therefore cannot
+//! provide a public key for the hiding commitments. Its `transcript.json` is read as a
+//! non-hiding setup by `eip4844::TrustedSetup::from_ceremony_json` instead.
+
+use crate::curve::{Curve, G1_BYTES, G2_BYTES};
+use crate::{KzgError, Pk};
+
+const MAGIC: &[u8; 4] = b"ptau";
+const HEADER_SECTION: u32 = 1;
+const TAU_G1_SECTION: u32 = 2;
+const TAU_G2_SECTION: u32 = 3;
+const BETA_TAU_G1_SECTION: u32 = 5;
+
+// bytes of a base field element
+const FP_BYTES: usize = 48;
+
+// the base field modulus p in little endian limbs
+const MODULUS: [u64; 6] = [
+    0xb9feffffffffaaab, 0x1eabfffeb153ffff, 0x6730d2a0f6b0f624,
+    0x64774b84f38512bf, 0x4b1ba7b6434bacd7, 0x1a0111ea397fe69a
+];
+// -p^-1 mod 2^64 for the Montgomery reduction
+const MODULUS_INV: u64 = 0x89f3fffcfffcfffd;
+// (p - 1) / 2 in little endian limbs, the largest y that has the sign bit cleared
+const HALF_MODULUS: [u64; 6] = [
+    0xdcff7fffffffd555, 0x0f55ffff58a9ffff, 0xb39869507b587b12,
+    0xb23ba5c279c2895f, 0x258dd3db21a5d66b, 0x0d0088f51cbff34d
+];
+
+
+impl<T: Curve> Pk<T> {
+    /// Imports the public key from a snarkjs `.ptau` transcript
+    ///
+    /// The powers are checked with `Pk::verify` before they are accepted: the G1 powers and
+    /// the hiding powers have to be successive powers of the same τ that the G2 powers encode.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The bytes of the `.ptau` file of a BLS12-381 ceremony
+    /// * `degree` - Maximum degree of polynomials that can be committed to
+    ///
+    /// # Returns
+    ///
+    /// A `Pk<T>` with α = τ and the hiding generator h = g^β of the ceremony
+    ///
+    /// # Errors
+    ///
+    /// * `KzgError::InvalidEncoding` if the transcript is not a BLS12-381 `.ptau` file or holds the identity
+    /// * `KzgError::SetTooLarge` if the ceremony has fewer powers than the degree needs
+    /// * `KzgError::PairingCheckFailed` if the powers are not consistent
+    ///
+    pub fn from_ceremony(transcript: &[u8], degree: usize) -> Result<Self, KzgError> {
+        let sections = read_sections(transcript)?;
+        let section = |kind: u32| {
+            sections.iter().find(|(k, _)| *k == kind).map(|(_, data)| *data).ok_or(KzgError::InvalidEncoding)
+        };
+
+        // n8, p and the power of the ceremony
+        let mut header = Cursor(section(HEADER_SECTION)?);
+        if header.u32()? as usize != FP_BYTES || header.fp_raw()? != MODULUS {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let power = header.u32()?;
+        if power >= usize::BITS - 1 {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let powers = 1usize << power;
+        if degree >= powers {
+            return Err(KzgError::SetTooLarge { max: powers - 1, got: degree });
+        }
+
+        // g^(τ^0), g^(τ^1), ..., lowest power first, at least two to read τ in both groups
+        let count = degree.max(1) + 1;
+        let tau_g1 = read_g1_points::<T>(section(TAU_G1_SECTION)?, count)?;
+        let tau_g2 = read_g2_points::<T>(section(TAU_G2_SECTION)?, count)?;
+        let beta_tau_g1 = read_g1_points::<T>(section(BETA_TAU_G1_SECTION)?, degree + 1)?;
+
+        let pk = Pk {
+            g_powers: tau_g1[..degree + 1].iter().rev().copied().collect(),
+            h_powers: beta_tau_g1.iter().rev().copied().collect(),
+            g2_powers: tau_g2[..degree + 1].iter().rev().copied().collect(),
+            h1: beta_tau_g1[0],
+            alpha_g2: tau_g2[1]
+        };
+
+        // g^τ and g2^τ encode the same τ, the rest is checked on the key
+        let identity = T::g1mul(&T::scalar_from_literal(&0), &T::g1());
+        let pairs = [(tau_g1[1], T::g2()), (T::g1sub(&identity, &T::g1()), tau_g2[1])];
+        if T::multi_pairing(&pairs) != T::gt_one() {
+            return Err(KzgError::PairingCheckFailed);
+        }
+        pk.verify()?;
+
+        Ok(pk)
+    }
+}
+
+
+// the sections of a ptau file, in file order
+fn read_sections(transcript: &[u8]) -> Result<Vec<(u32, &[u8])>, KzgError> {
+    let mut cursor = Cursor(transcript);
+    if cursor.take(MAGIC.len())? != MAGIC {
+        return Err(KzgError::InvalidEncoding);
+    }
+    let _version = cursor.u32()?;
+    let count = cursor.u32()?;
+
+    let mut sections = Vec::new();
+    for _ in 0..count {
+        let kind = cursor.u32()?;
+        let size = usize::try_from(cursor.u64()?).map_err(|_| KzgError::InvalidEncoding)?;
+        sections.push((kind, cursor.take(size)?));
+    }
+    Ok(sections)
+}
+
+fn read_g1_points<T: Curve>(data: &[u8], count: usize) -> Result<Vec<T::G1>, KzgError> {
+    let mut cursor = Cursor(data);
+    (0..count).map(|_| {
+        let x = cursor.fp()?;
+        let y = cursor.fp()?;
+        let bytes = compress::<G1_BYTES>(&[x], &[y]);
+        T::g1_from_bytes(&bytes).ok_or(KzgError::InvalidEncoding)
+    }).collect()
+}
+
+fn read_g2_points<T: Curve>(data: &[u8], count: usize) -> Result<Vec<T::G2>, KzgError> {
+    let mut cursor = Cursor(data);
+    (0..count).map(|_| {
+        // c0 before c1 in the transcript, the compressed encoding starts with c1
+        let (x0, x1) = (cursor.fp()?, cursor.fp()?);
+        let (y0, y1) = (cursor.fp()?, cursor.fp()?);
+        let bytes = compress::<G2_BYTES>(&[x1, x0], &[y1, y0]);
+        T::g2_from_bytes(&bytes).ok_or(KzgError::InvalidEncoding)
+    }).collect()
+}
+
+// the compressed encoding of the point with coordinates x and y, highest coefficient first,
+// where the sign bit tells whether y is lexicographically larger than -y
+fn compress<const N: usize>(x: &[[u64; 6]], y: &[[u64; 6]]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    for (chunk, limbs) in bytes.chunks_mut(FP_BYTES).zip(x) {
+        for (i, limb) in limbs.iter().rev().enumerate() {
+            chunk[8 * i..8 * i + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+    }
+
+    let zero = [0u64; 6];
+    if x.iter().chain(y).all(|c| *c == zero) {
+        // snarkjs writes the point at infinity as zero coordinates
+        bytes[0] = 0xc0;
+        return bytes;
+    }
+
+    let sign = y.iter().find(|c| **c != zero).is_some_and(|c| greater(c, &HALF_MODULUS));
+    bytes[0] |= 0x80 | if sign { 0x20 } else { 0 };
+    bytes
+}
+
+// a > b for little endian limbs
+fn greater(a: &[u64; 6], b: &[u64; 6]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()) == std::cmp::Ordering::Greater
+}
+
+// reduces x·R to x for R = 2^384, one limb at a time
+fn from_montgomery(limbs: [u64; 6]) -> [u64; 6] {
+    let mut t = [0u64; 7];
+    t[..6].copy_from_slice(&limbs);
+
+    for _ in 0..6 {
+        // adding m·p makes the lowest limb zero
+        let m = t[0].wrapping_mul(MODULUS_INV);
+        let mut carry = 0u128;
+        for j in 0..6 {
+            let sum = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry;
+            t[j] = sum as u64;
+            carry = sum >> 64;
+        }
+        t[6] = (t[6] as u128 + carry) as u64;
+
+        t.copy_within(1.., 0);
+        t[6] = 0;
+    }
+
+    let mut result = [0u64; 6];
+    result.copy_from_slice(&t[..6]);
+    if !greater(&MODULUS, &result) {
+        let mut borrow = false;
+        for (r, p) in result.iter_mut().zip(MODULUS) {
+            let (difference, b1) = r.overflowing_sub(p);
+            let (difference, b2) = difference.overflowing_sub(borrow as u64);
+            *r = difference;
+            borrow = b1 || b2;
+        }
+    }
+    result
+}
+
+// reads little endian values from the front of a byte slice
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], KzgError> {
+        if self.0.len() < n {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, KzgError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("took 4 bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64, KzgError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("took 8 bytes")))
+    }
+
+    fn fp_raw(&mut self) -> Result<[u64; 6], KzgError> {
+        let bytes = self.take(FP_BYTES)?;
+        let mut limbs = [0u64; 6];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("chunks of 8 bytes"));
+        }
+        Ok(limbs)
+    }
+
+    // a coordinate in Montgomery form, which has to be reduced
+    fn fp(&mut self) -> Result<[u64; 6], KzgError> {
+        let limbs = self.fp_raw()?;
+        if !greater(&MODULUS, &limbs) {
+            return Err(KzgError::InvalidEncoding);
+        }
+        Ok(from_montgomery(limbs))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use group::Group;
+    use crate::curve::FastCurve;
+    use crate::{commitzk, queryzk, verifyzk};
+    use super::*;
+
+    // x·R mod p by doubling x 384 times
+    fn to_montgomery(mut x: [u64; 6]) -> [u64; 6] {
+        for _ in 0..384 {
+            let mut carry = 0;
+            for limb in x.iter_mut() {
+                let next = *limb >> 63;
+                *limb = (*limb << 1) | carry;
+                carry = next;
+            }
+            // x < p < 2^381, so doubling never overflows the limbs
+            if !greater(&MODULUS, &x) {
+                let mut borrow = false;
+                for (r, p) in x.iter_mut().zip(MODULUS) {
+                    let (difference, b1) = r.overflowing_sub(p);
+                    let (difference, b2) = difference.overflowing_sub(borrow as u64);
+                    *r = difference;
+                    borrow = b1 || b2;
+                }
+            }
+        }
+        x
+    }
+
+    // big endian coordinates to Montgomery form little endian bytes
+    fn write_fp(out: &mut Vec<u8>, be: &[u8]) {
+        let mut limbs = [0u64; 6];
+        for (i, chunk) in be.chunks(8).rev().enumerate() {
+            limbs[i] = u64::from_be_bytes(chunk.try_into().expect("chunks of 8 bytes"));
+        }
+        for limb in to_montgomery(limbs) {
+            out.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    fn write_g1(out: &mut Vec<u8>, point: &blstrs::G1Projective) {
+        let bytes = blstrs::G1Affine::from(point).to_uncompressed();
+        if bool::from(point.is_identity()) {
+            out.extend_from_slice(&[0u8; 2 * FP_BYTES]);
+            return;
+        }
+        write_fp(out, &bytes[..FP_BYTES]);
+        write_fp(out, &bytes[FP_BYTES..]);
+    }
+
+    fn write_g2(out: &mut Vec<u8>, point: &blstrs::G2Projective) {
+        // x.c1, x.c0, y.c1, y.c0 in the uncompressed encoding
+        let bytes = blstrs::G2Affine::from(point).to_uncompressed();
+        for i in [1, 0, 3, 2] {
+            write_fp(out, &bytes[i * FP_BYTES..(i + 1) * FP_BYTES]);
+        }
+    }
+
+    fn section(out: &mut Vec<u8>, kind: u32, data: &[u8]) {
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        out.extend_from_slice(data);
+    }
+
+    // a ptau file of the given power for known τ and β
+    fn transcript(power: u32, tau: u128, beta: u128) -> Vec<u8> {
+        use crate::curve::FastCurve as Curve;
+        let size = 1usize << power;
+        let tau = Curve::scalar_from_literal(&tau);
+        let beta = Curve::scalar_from_literal(&beta);
+        let tau_power = |i: usize| Curve::scalar_pow(&tau, &(i as u128));
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(FP_BYTES as u32).to_le_bytes());
+        for limb in MODULUS {
+            header.extend_from_slice(&limb.to_le_bytes());
+        }
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+
+        let mut tau_g1 = Vec::new();
+        for i in 0..2 * size - 1 {
+            write_g1(&mut tau_g1, &Curve::g1mul(&tau_power(i), &Curve::g1()));
+        }
+        let mut tau_g2 = Vec::new();
+        for i in 0..size {
+            write_g2(&mut tau_g2, &Curve::g2mul(&tau_power(i), &Curve::g2()));
+        }
+        let mut beta_tau_g1 = Vec::new();
+        for i in 0..size {
+            write_g1(&mut beta_tau_g1, &Curve::g1mul(&(beta * tau_power(i)), &Curve::g1()));
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&1u32.to_le_bytes());
+        out.extend_from_slice(&4u32.to_le_bytes());
+        section(&mut out, HEADER_SECTION, &header);
+        section(&mut out, TAU_G1_SECTION, &tau_g1);
+        section(&mut out, TAU_G2_SECTION, &tau_g2);
+        section(&mut out, BETA_TAU_G1_SECTION, &beta_tau_g1);
+        out
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let x = [1, 2, 3, 4, 5, 6];
+        assert_eq!(from_montgomery(to_montgomery(x)), x);
+        assert_eq!(from_montgomery(to_montgomery([0; 6])), [0; 6]);
+    }
+
+    // the imported key has α = τ and works with the protocol
+    #[test]
+    fn test_from_ceremony() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk = Pk::<Curve>::from_ceremony(&transcript(3, 5, 7), 6).expect("transcript is valid");
+
+        let tau = Curve::scalar_from_literal(&5);
+        assert_eq!(pk.g_powers.len(), 7);
+        assert_eq!(pk.g_powers[5], Curve::g1mul(&tau, &Curve::g1()));
+        assert_eq!(pk.h1, Curve::g1mul(&Curve::scalar_from_literal(&7), &Curve::g1()));
+        assert_eq!(pk.alpha_g2, Curve::g2mul(&tau, &Curve::g2()));
+
+        let set: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let kj = Curve::scalar_from_literal(&2);
+        let proof = queryzk(&pk, &opening, kj, &mut rng).expect("opening is valid");
+        assert!(verifyzk(&pk, &commitment, kj, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_ceremony() {
+        let valid = transcript(2, 5, 7);
+
+        assert_eq!(Pk::<FastCurve>::from_ceremony(&valid, 4).err(), Some(KzgError::SetTooLarge { max: 3, got: 4 }));
+        assert_eq!(Pk::<FastCurve>::from_ceremony(&valid[..100], 3).err(), Some(KzgError::InvalidEncoding));
+
+        let mut wrong_magic = valid.clone();
+        wrong_magic[0] = b'x';
+        assert_eq!(Pk::<FastCurve>::from_ceremony(&wrong_magic, 3).err(), Some(KzgError::InvalidEncoding));
+
+        // replace g^(τ^2) by g^(τ^2 + 1), a valid point that breaks the powers
+        let mut forged = valid.clone();
+        let offset = 12 + 12 + 4 + FP_BYTES + 8 + 12 + 2 * 2 * FP_BYTES;
+        let mut point = Vec::new();
+        write_g1(&mut point, &FastCurve::g1mul(&FastCurve::scalar_from_literal(&26), &FastCurve::g1()));
+        forged[offset..offset + point.len()].copy_from_slice(&point);
+        assert_eq!(Pk::<FastCurve>::from_ceremony(&forged, 3).err(), Some(KzgError::PairingCheckFailed));
+    }
+}
@@ -0,0 +1,295 @@
+//! # Serde Support
+//!
+//! `Serialize` and `Deserialize` for `Pk`, `Commitment`, `Opening`, `SetUpdate`, the ceremony
+//! `Transcript` and the proof types behind the `serde` feature.
+//!
+//! Values are serialized using the canonical byte encodings of the `serialization` module.
+//! Human readable formats such as JSON get a lowercase hex string, binary formats such as
+//! CBOR get the raw bytes.
+
+use std::fmt;
+
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ceremony::{ContributionProof, Transcript};
+use crate::curve::{Curve, G1_BYTES};
+use crate::disjoint::DisjointnessProof;
+use crate::intersection::IntersectionProof;
+use crate::multiset::MultiplicityProof;
+use crate::subset::SubsetProof;
+use crate::update::SetUpdate;
+use crate::{BatchMembershipProof, BatchNonMembershipProof, Commitment, MembershipProof, Opening, Pk};
+
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        let hex_string = String::deserialize(deserializer)?;
+        let hex_string = hex_string.strip_prefix("0x").unwrap_or(&hex_string);
+        hex::decode(hex_string).map_err(D::Error::custom)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    // some binary formats encode bytes as a sequence of integers
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+
+// Serialize and Deserialize through the canonical encoding of a type
+macro_rules! serde_via_bytes {
+    (@serialize $ty:ident) => {
+        impl<T: Curve> Serialize for $ty<T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&self.to_bytes(), serializer)
+            }
+        }
+    };
+    // an encoding of fixed length is decoded from an array
+    ($ty:ident, $len:expr) => {
+        serde_via_bytes!(@serialize $ty);
+
+        impl<'de, T: Curve> Deserialize<'de> for $ty<T> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(deserializer)?;
+                let bytes: [u8; $len] = bytes.try_into().map_err(|_| D::Error::custom(concat!("invalid ", stringify!($ty), " length")))?;
+                $ty::from_bytes(&bytes).map_err(D::Error::custom)
+            }
+        }
+    };
+    ($ty:ident) => {
+        serde_via_bytes!(@serialize $ty);
+
+        impl<'de, T: Curve> Deserialize<'de> for $ty<T> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(deserializer)?;
+                $ty::from_bytes(&bytes).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+serde_via_bytes!(Pk);
+serde_via_bytes!(Commitment, G1_BYTES);
+serde_via_bytes!(Opening);
+serde_via_bytes!(MembershipProof);
+serde_via_bytes!(BatchMembershipProof);
+serde_via_bytes!(BatchNonMembershipProof);
+serde_via_bytes!(SetUpdate);
+serde_via_bytes!(MultiplicityProof);
+serde_via_bytes!(SubsetProof);
+serde_via_bytes!(DisjointnessProof);
+serde_via_bytes!(IntersectionProof);
+serde_via_bytes!(ContributionProof);
+serde_via_bytes!(Transcript);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::curve::{FastCurve, SpecCurve};
+    use crate::tape::Tape;
+    use crate::{commitzk, query_members_batch, query_non_members_batch, queryzk, setup};
+    use super::*;
+
+    // a public key and a commitment to {1, 2, 3} with its opening
+    fn committed_set() -> (Pk<FastCurve>, Commitment<FastCurve>, Opening<FastCurve>) {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(4, &mut rng);
+        let set: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        (pk, commitment, opening)
+    }
+
+    fn cbor_roundtrip<V: Serialize + for<'de> Deserialize<'de>>(value: &V) -> V {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).expect("serialization succeeds");
+        ciborium::from_reader(&bytes[..]).expect("deserialization succeeds")
+    }
+
+    fn json_roundtrip<V: Serialize + for<'de> Deserialize<'de>>(value: &V) -> V {
+        let json = serde_json::to_string(value).expect("serialization succeeds");
+        serde_json::from_str(&json).expect("deserialization succeeds")
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(4, &mut rng);
+
+        let set: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let member = queryzk(&pk, &opening, FastCurve::scalar_from_literal(&2), &mut rng).expect("opening fits the public key");
+        let non_member = queryzk(&pk, &opening, FastCurve::scalar_from_literal(&4), &mut rng).expect("opening fits the public key");
+
+        assert_eq!(json_roundtrip(&pk).to_bytes(), pk.to_bytes());
+        assert_eq!(cbor_roundtrip(&pk).to_bytes(), pk.to_bytes());
+        assert_eq!(json_roundtrip(&commitment), commitment);
+        assert_eq!(cbor_roundtrip(&commitment), commitment);
+        assert_eq!(json_roundtrip(&opening), opening);
+        assert_eq!(cbor_roundtrip(&opening), opening);
+        assert_eq!(json_roundtrip(&member), member);
+        assert_eq!(cbor_roundtrip(&non_member), non_member);
+    }
+
+    #[test]
+    fn test_batch_membership_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let members: Vec<_> = [2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = query_members_batch(&pk, &opening, &members).expect("elements are in the set");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_batch_non_membership_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let non_members: Vec<_> = [4, 5].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = query_non_members_batch(&pk, &opening, &non_members, &mut rng).expect("elements are not in the set");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_set_update_roundtrip() {
+        let (pk, mut commitment, mut opening) = committed_set();
+        let four = FastCurve::scalar_from_literal(&4);
+
+        let insert = crate::update::insert(&pk, &mut opening, &mut commitment, four).expect("set fits the public key");
+        let remove = crate::update::remove(&pk, &mut opening, &mut commitment, four).expect("4 is in the set");
+        assert_eq!(json_roundtrip(&insert), insert);
+        assert_eq!(cbor_roundtrip(&insert), insert);
+        assert_eq!(json_roundtrip(&remove), remove);
+        assert_eq!(cbor_roundtrip(&remove), remove);
+    }
+
+    #[test]
+    fn test_multiplicity_proof_roundtrip() {
+        let (pk, _, _) = committed_set();
+        let mut rng = rand::rng();
+        let two = FastCurve::scalar_from_literal(&2);
+
+        let (_, opening) = crate::multiset::commit_multiset(&pk, &[two, two], &mut rng).expect("multiset fits the public key");
+        let proof = crate::multiset::query_multiplicity(&pk, &opening, two, 2, &mut rng).expect("2 occurs twice");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_subset_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let subset: Vec<_> = [1, 2].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::subset::prove_subset(&pk, &opening, &subset, &mut rng).expect("subset is in the set");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_disjointness_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let elements: Vec<_> = [4, 5].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::disjoint::prove_disjoint(&pk, &opening, &elements, &mut rng).expect("no element is in the set");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_intersection_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let other: HashSet<_> = [3, 4].iter().map(FastCurve::scalar_from_literal).collect();
+        let (_, other) = commitzk(&pk, &other, &mut rng).expect("set fits the public key");
+
+        let (_, proof) = crate::intersection::prove_intersection(&pk, &opening, &other, &mut rng).expect("openings fit the public key");
+        assert_eq!(json_roundtrip(&proof), proof);
+        assert_eq!(cbor_roundtrip(&proof), proof);
+    }
+
+    #[test]
+    fn test_ceremony_roundtrip() {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(4, &mut rng);
+
+        let (next, contribution) = crate::ceremony::contribute(&pk, &mut rng);
+        assert_eq!(json_roundtrip(&contribution), contribution);
+        assert_eq!(cbor_roundtrip(&contribution), contribution);
+
+        let mut transcript = Transcript::new(next);
+        transcript.contribute(&mut rng);
+        assert_eq!(json_roundtrip(&transcript).to_bytes(), transcript.to_bytes());
+        assert_eq!(cbor_roundtrip(&transcript).to_bytes(), transcript.to_bytes());
+    }
+
+    #[test]
+    fn test_formats() {
+        let commitment = Commitment::<FastCurve>(FastCurve::g1());
+
+        let json = serde_json::to_string(&commitment).expect("serialization succeeds");
+        assert_eq!(json, format!("\"{}\"", hex::encode(commitment.to_bytes())));
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&commitment, &mut cbor).expect("serialization succeeds");
+        // a byte string of length 48 followed by the raw encoding
+        assert_eq!(&cbor[..2], &[0x58, 48]);
+        assert_eq!(&cbor[2..], &commitment.to_bytes()[..]);
+
+        assert!(serde_json::from_str::<Commitment<FastCurve>>("\"00\"").is_err());
+    }
+
+    // values serialized with one curve implementation can be read with the other
+    #[test]
+    fn test_backends_interoperate() {
+        let values: Vec<u128> = (1..=40).collect();
+
+        let mut random = Tape::new(values);
+        let pk: Pk<SpecCurve> = setup(2, &mut random);
+        let set: HashSet<_> = [7].iter().map(SpecCurve::scalar_from_literal).collect();
+        let (commitment, _) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let json = serde_json::to_string(&commitment).expect("serialization succeeds");
+        let decoded: Commitment<FastCurve> = serde_json::from_str(&json).expect("deserialization succeeds");
+        assert_eq!(decoded.to_bytes(), commitment.to_bytes());
+
+        let json = serde_json::to_string(&pk).expect("serialization succeeds");
+        let decoded: Pk<FastCurve> = serde_json::from_str(&json).expect("deserialization succeeds");
+        assert_eq!(decoded.to_bytes(), pk.to_bytes());
+    }
+}
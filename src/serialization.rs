@@ -0,0 +1,922 @@
+//! # Canonical Byte Encodings
+//!
+//! Byte encodings for the public key, commitments and membership proofs.
+//!
+//! Points are encoded compressed (48 bytes for G1, 96 bytes for G2) and scalars as
+//! 32 big endian bytes, see `Curve::g1_to_bytes` and friends.
+//! Both curve implementations produce identical bytes, so a value encoded with
+//! `SpecCurve` can be decoded with `FastCurve` and the other way around.
+//!
+//! Decoding rejects trailing bytes, non-canonical encodings and points that are
+//! not in the prime order subgroup.
+//!
+//! ## Formats
+//!
+//! * `Pk` - n as 8 byte big endian integer, n `g_powers`, n `h_powers`, n `g2_powers`, `h1`, `alpha_g2`
+//! * `Commitment` - the compressed commitment
+//! * `Opening` - n and m as 8 byte big endian integers, n coefficients of `phi`, m coefficients of `phi_hat`
+//! * `MembershipProof` - a tag byte followed by the fields in declaration order,
+//!   `0` for `Member` and `1` for `NonMember`
+//! * `BatchMembershipProof` - n as 8 byte big endian integer, `witness`, n coefficients of `remainder`
+//! * `BatchNonMembershipProof` - the fields in declaration order
+//! * `SetUpdate` - a tag byte followed by the fields in declaration order,
+//!   `0` for `Insert` and `1` for `Remove`
+//! * `MultiplicityProof` - n as 8 byte big endian integer, `witness`, n coefficients of `remainder`,
+//!   then the encoding of `quotient_proof`
+//! * `SubsetProof` - n as 8 byte big endian integer, `witness`, `remainder`, `nonce`,
+//!   n coefficients of `response`
+//! * `DisjointnessProof` - n as 8 byte big endian integer, `bezout_a`, `bezout_b`, `remainder`,
+//!   `nonce`, n coefficients of `response`
+//! * `IntersectionProof` - `cardinality` as 8 byte big endian integer followed by the fields
+//!   in declaration order, every `ProductProof` as its `nonces` and `responses`
+//! * `ContributionProof` - the fields in declaration order
+//! * `Transcript` - the initial and the current `Pk`, m as 8 byte big endian integer,
+//!   then m `ContributionProof`s
+
+use crate::ceremony::{ContributionProof, Transcript};
+use crate::curve::{Curve, G1_BYTES, G2_BYTES, SCALAR_BYTES};
+use crate::disjoint::DisjointnessProof;
+use crate::intersection::{IntersectionProof, ProductProof};
+use crate::multiset::MultiplicityProof;
+use crate::subset::SubsetProof;
+use crate::update::SetUpdate;
+use crate::{BatchMembershipProof, BatchNonMembershipProof, Commitment, KzgError, MembershipProof, Opening, Pk};
+
+const MEMBER_TAG: u8 = 0;
+const NON_MEMBER_TAG: u8 = 1;
+const INSERT_TAG: u8 = 0;
+const REMOVE_TAG: u8 = 1;
+const BATCH_NON_MEMBERSHIP_PROOF_BYTES: usize = 5 * G1_BYTES + 3 * SCALAR_BYTES;
+const CONTRIBUTION_PROOF_BYTES: usize = G2_BYTES + 2 * G1_BYTES + SCALAR_BYTES;
+
+// reads values from the front of a byte slice
+struct Reader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], KzgError> {
+        if self.bytes.len() < N {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+        head.try_into().map_err(|_| KzgError::InvalidEncoding)
+    }
+
+    fn u64(&mut self) -> Result<u64, KzgError> {
+        Ok(u64::from_be_bytes(self.take()?))
+    }
+
+    fn g1<T: Curve>(&mut self) -> Result<T::G1, KzgError> {
+        T::g1_from_bytes(&self.take::<G1_BYTES>()?).ok_or(KzgError::InvalidEncoding)
+    }
+
+    fn g2<T: Curve>(&mut self) -> Result<T::G2, KzgError> {
+        T::g2_from_bytes(&self.take::<G2_BYTES>()?).ok_or(KzgError::InvalidEncoding)
+    }
+
+    fn scalar<T: Curve>(&mut self) -> Result<T::Scalar, KzgError> {
+        T::scalar_from_bytes(&self.take::<SCALAR_BYTES>()?).ok_or(KzgError::InvalidEncoding)
+    }
+
+    fn g1s<T: Curve, const N: usize>(&mut self) -> Result<[T::G1; N], KzgError> {
+        let points = (0..N).map(|_| self.g1::<T>()).collect::<Result<Vec<_>, _>>()?;
+        points.try_into().map_err(|_| KzgError::InvalidEncoding)
+    }
+
+    fn scalars<T: Curve, const N: usize>(&mut self) -> Result<[T::Scalar; N], KzgError> {
+        let scalars = (0..N).map(|_| self.scalar::<T>()).collect::<Result<Vec<_>, _>>()?;
+        scalars.try_into().map_err(|_| KzgError::InvalidEncoding)
+    }
+
+    // takes all remaining bytes
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.bytes)
+    }
+
+    // succeeds only if every byte has been read
+    fn finish(self) -> Result<(), KzgError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(KzgError::InvalidEncoding)
+        }
+    }
+}
+
+
+impl<T: Curve> Pk<T> {
+    /// Encodes the public key
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.g_powers.len();
+        let mut bytes = Vec::with_capacity(8 + n * (2 * G1_BYTES + G2_BYTES) + G1_BYTES + G2_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        for power in self.g_powers.iter().chain(self.h_powers.iter()) {
+            bytes.extend_from_slice(&T::g1_to_bytes(power));
+        }
+        for power in self.g2_powers.iter() {
+            bytes.extend_from_slice(&T::g2_to_bytes(power));
+        }
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.h1));
+        bytes.extend_from_slice(&T::g2_to_bytes(&self.alpha_g2));
+
+        bytes
+    }
+
+    /// Decodes a public key created by `to_bytes`
+    ///
+    /// Besides decoding every point this checks that the last powers are g, h and g2
+    /// and that `alpha_g2` is the second to last power of g2
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        // avoid allocating for a length the input cannot contain
+        if n == 0 || n > bytes.len() / (2 * G1_BYTES + G2_BYTES) {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let mut g_powers = Vec::with_capacity(n);
+        for _ in 0..n {
+            g_powers.push(reader.g1::<T>()?);
+        }
+        let mut h_powers = Vec::with_capacity(n);
+        for _ in 0..n {
+            h_powers.push(reader.g1::<T>()?);
+        }
+        let mut g2_powers = Vec::with_capacity(n);
+        for _ in 0..n {
+            g2_powers.push(reader.g2::<T>()?);
+        }
+        let h1 = reader.g1::<T>()?;
+        let alpha_g2 = reader.g2::<T>()?;
+        reader.finish()?;
+
+        if g_powers[n - 1] != T::g1() || h_powers[n - 1] != h1 || g2_powers[n - 1] != T::g2() {
+            return Err(KzgError::InvalidEncoding);
+        }
+        if n > 1 && g2_powers[n - 2] != alpha_g2 {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        Ok(Pk { g_powers, h_powers, g2_powers, h1, alpha_g2 })
+    }
+}
+
+
+impl<T: Curve> Commitment<T> {
+    /// Encodes the commitment as a compressed G1 point
+    pub fn to_bytes(&self) -> [u8; G1_BYTES] {
+        T::g1_to_bytes(&self.0)
+    }
+
+    /// Decodes a commitment created by `to_bytes`
+    pub fn from_bytes(bytes: &[u8; G1_BYTES]) -> Result<Self, KzgError> {
+        T::g1_from_bytes(bytes).map(Commitment).ok_or(KzgError::InvalidEncoding)
+    }
+}
+
+
+impl<T: Curve> Opening<T> {
+    /// Encodes the opening
+    ///
+    /// The opening is the prover's secret and should only be stored or sent over secure channels
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // both lengths, so that an opening with mismatched polynomials still decodes to itself
+        let (n, m) = (self.phi.len(), self.phi_hat.len());
+        let mut bytes = Vec::with_capacity(16 + (n + m) * SCALAR_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        bytes.extend_from_slice(&(m as u64).to_be_bytes());
+        for coefficient in self.phi.iter().chain(self.phi_hat.iter()) {
+            bytes.extend_from_slice(&T::scalar_to_bytes(coefficient));
+        }
+
+        bytes
+    }
+
+    /// Decodes an opening created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        let m = reader.u64()? as usize;
+        let max = bytes.len() / SCALAR_BYTES;
+        if n == 0 || n > max || m > max - n {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let mut phi = Vec::with_capacity(n);
+        for _ in 0..n {
+            phi.push(reader.scalar::<T>()?);
+        }
+        let mut phi_hat = Vec::with_capacity(m);
+        for _ in 0..m {
+            phi_hat.push(reader.scalar::<T>()?);
+        }
+        reader.finish()?;
+
+        Ok(Opening { phi, phi_hat })
+    }
+}
+
+
+impl<T: Curve> MembershipProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            MembershipProof::Member { witness, phi_hat_kj } => {
+                bytes.push(MEMBER_TAG);
+                bytes.extend_from_slice(&T::g1_to_bytes(witness));
+                bytes.extend_from_slice(&T::scalar_to_bytes(phi_hat_kj));
+            },
+            MembershipProof::NonMember { witness, proof, n1, n2, s1, s2 } => {
+                bytes.push(NON_MEMBER_TAG);
+                for point in [witness, proof, n1, n2] {
+                    bytes.extend_from_slice(&T::g1_to_bytes(point));
+                }
+                bytes.extend_from_slice(&T::scalar_to_bytes(s1));
+                bytes.extend_from_slice(&T::scalar_to_bytes(s2));
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let proof = match reader.take::<1>()?[0] {
+            MEMBER_TAG => MembershipProof::Member {
+                witness: reader.g1::<T>()?,
+                phi_hat_kj: reader.scalar::<T>()?
+            },
+            NON_MEMBER_TAG => MembershipProof::NonMember {
+                witness: reader.g1::<T>()?,
+                proof: reader.g1::<T>()?,
+                n1: reader.g1::<T>()?,
+                n2: reader.g1::<T>()?,
+                s1: reader.scalar::<T>()?,
+                s2: reader.scalar::<T>()?
+            },
+            _ => return Err(KzgError::InvalidEncoding)
+        };
+        reader.finish()?;
+
+        Ok(proof)
+    }
+}
+
+
+impl<T: Curve> BatchMembershipProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.remainder.len();
+        let mut bytes = Vec::with_capacity(8 + G1_BYTES + n * SCALAR_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.witness));
+        for coefficient in self.remainder.iter() {
+            bytes.extend_from_slice(&T::scalar_to_bytes(coefficient));
+        }
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        if n > bytes.len() / SCALAR_BYTES {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let witness = reader.g1::<T>()?;
+        let mut remainder = Vec::with_capacity(n);
+        for _ in 0..n {
+            remainder.push(reader.scalar::<T>()?);
+        }
+        reader.finish()?;
+
+        Ok(BatchMembershipProof { witness, remainder })
+    }
+}
+
+
+impl<T: Curve> BatchNonMembershipProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BATCH_NON_MEMBERSHIP_PROOF_BYTES);
+
+        for point in [&self.witness, &self.remainder, &self.inverse, &self.cofactor, &self.opening] {
+            bytes.extend_from_slice(&T::g1_to_bytes(point));
+        }
+        for x in [&self.remainder_z, &self.inverse_z, &self.hiding_z] {
+            bytes.extend_from_slice(&T::scalar_to_bytes(x));
+        }
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let proof = BatchNonMembershipProof {
+            witness: reader.g1::<T>()?,
+            remainder: reader.g1::<T>()?,
+            inverse: reader.g1::<T>()?,
+            cofactor: reader.g1::<T>()?,
+            opening: reader.g1::<T>()?,
+            remainder_z: reader.scalar::<T>()?,
+            inverse_z: reader.scalar::<T>()?,
+            hiding_z: reader.scalar::<T>()?
+        };
+        reader.finish()?;
+
+        Ok(proof)
+    }
+}
+
+
+impl<T: Curve> SetUpdate<T> {
+    /// Encodes the update
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            SetUpdate::Insert { element, old_commitment } => {
+                bytes.push(INSERT_TAG);
+                bytes.extend_from_slice(&T::scalar_to_bytes(element));
+                bytes.extend_from_slice(&old_commitment.to_bytes());
+            },
+            SetUpdate::Remove { element, new_commitment, hiding_evaluation } => {
+                bytes.push(REMOVE_TAG);
+                bytes.extend_from_slice(&T::scalar_to_bytes(element));
+                bytes.extend_from_slice(&new_commitment.to_bytes());
+                bytes.extend_from_slice(&T::scalar_to_bytes(hiding_evaluation));
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes an update created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let update = match reader.take::<1>()?[0] {
+            INSERT_TAG => SetUpdate::Insert {
+                element: reader.scalar::<T>()?,
+                old_commitment: Commitment(reader.g1::<T>()?)
+            },
+            REMOVE_TAG => SetUpdate::Remove {
+                element: reader.scalar::<T>()?,
+                new_commitment: Commitment(reader.g1::<T>()?),
+                hiding_evaluation: reader.scalar::<T>()?
+            },
+            _ => return Err(KzgError::InvalidEncoding)
+        };
+        reader.finish()?;
+
+        Ok(update)
+    }
+}
+
+
+impl<T: Curve> MultiplicityProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.remainder.len();
+        let mut bytes = Vec::with_capacity(8 + G1_BYTES + n * SCALAR_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.witness));
+        for coefficient in self.remainder.iter() {
+            bytes.extend_from_slice(&T::scalar_to_bytes(coefficient));
+        }
+        bytes.extend_from_slice(&self.quotient_proof.to_bytes());
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        if n > bytes.len() / SCALAR_BYTES {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let witness = reader.g1::<T>()?;
+        let mut remainder = Vec::with_capacity(n);
+        for _ in 0..n {
+            remainder.push(reader.scalar::<T>()?);
+        }
+        let quotient_proof = MembershipProof::from_bytes(reader.rest())?;
+
+        Ok(MultiplicityProof { witness, remainder, quotient_proof })
+    }
+}
+
+
+impl<T: Curve> SubsetProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.response.len();
+        let mut bytes = Vec::with_capacity(8 + 3 * G1_BYTES + n * SCALAR_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        for point in [&self.witness, &self.remainder, &self.nonce] {
+            bytes.extend_from_slice(&T::g1_to_bytes(point));
+        }
+        for coefficient in self.response.iter() {
+            bytes.extend_from_slice(&T::scalar_to_bytes(coefficient));
+        }
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        if n > bytes.len() / SCALAR_BYTES {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let witness = reader.g1::<T>()?;
+        let remainder = reader.g1::<T>()?;
+        let nonce = reader.g1::<T>()?;
+        let mut response = Vec::with_capacity(n);
+        for _ in 0..n {
+            response.push(reader.scalar::<T>()?);
+        }
+        reader.finish()?;
+
+        Ok(SubsetProof { witness, remainder, nonce, response })
+    }
+}
+
+
+impl<T: Curve> DisjointnessProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.response.len();
+        let mut bytes = Vec::with_capacity(8 + G2_BYTES + 3 * G1_BYTES + n * SCALAR_BYTES);
+
+        bytes.extend_from_slice(&(n as u64).to_be_bytes());
+        bytes.extend_from_slice(&T::g2_to_bytes(&self.bezout_a));
+        for point in [&self.bezout_b, &self.remainder, &self.nonce] {
+            bytes.extend_from_slice(&T::g1_to_bytes(point));
+        }
+        for coefficient in self.response.iter() {
+            bytes.extend_from_slice(&T::scalar_to_bytes(coefficient));
+        }
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let n = reader.u64()? as usize;
+        if n > bytes.len() / SCALAR_BYTES {
+            return Err(KzgError::InvalidEncoding);
+        }
+
+        let bezout_a = reader.g2::<T>()?;
+        let bezout_b = reader.g1::<T>()?;
+        let remainder = reader.g1::<T>()?;
+        let nonce = reader.g1::<T>()?;
+        let mut response = Vec::with_capacity(n);
+        for _ in 0..n {
+            response.push(reader.scalar::<T>()?);
+        }
+        reader.finish()?;
+
+        Ok(DisjointnessProof { bezout_a, bezout_b, remainder, nonce, response })
+    }
+}
+
+
+impl<T: Curve> IntersectionProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&(self.cardinality as u64).to_be_bytes());
+        let points = [self.intersection.0, self.degree].into_iter()
+            .chain(self.cofactors)
+            .chain(self.bezout)
+            .chain(self.evaluations)
+            .chain([self.witness])
+            .chain(self.products);
+        for point in points {
+            bytes.extend_from_slice(&T::g1_to_bytes(&point));
+        }
+        for product_proof in self.product_proofs.iter() {
+            for point in product_proof.nonces.iter() {
+                bytes.extend_from_slice(&T::g1_to_bytes(point));
+            }
+            for scalar in product_proof.responses.iter() {
+                bytes.extend_from_slice(&T::scalar_to_bytes(scalar));
+            }
+        }
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.nonce));
+        bytes.extend_from_slice(&T::scalar_to_bytes(&self.response));
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+
+        let cardinality = usize::try_from(reader.u64()?).map_err(|_| KzgError::InvalidEncoding)?;
+        let intersection = Commitment(reader.g1::<T>()?);
+        let degree = reader.g1::<T>()?;
+        let cofactors = reader.g1s::<T, 2>()?;
+        let bezout = reader.g1s::<T, 2>()?;
+        let evaluations = reader.g1s::<T, 7>()?;
+        let witness = reader.g1::<T>()?;
+        let products = reader.g1s::<T, 2>()?;
+
+        let mut product_proofs = Vec::with_capacity(4);
+        for _ in 0..4 {
+            let nonces = reader.g1s::<T, 2>()?;
+            let responses = reader.scalars::<T, 3>()?;
+            product_proofs.push(ProductProof { nonces, responses });
+        }
+        let product_proofs = product_proofs.try_into().map_err(|_| KzgError::InvalidEncoding)?;
+
+        let nonce = reader.g1::<T>()?;
+        let response = reader.scalar::<T>()?;
+        reader.finish()?;
+
+        Ok(IntersectionProof {
+            cardinality, intersection, degree, cofactors, bezout, evaluations,
+            witness, products, product_proofs, nonce, response
+        })
+    }
+}
+
+
+
+impl<T: Curve> ContributionProof<T> {
+    /// Encodes the proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CONTRIBUTION_PROOF_BYTES);
+
+        bytes.extend_from_slice(&T::g2_to_bytes(&self.alpha_g2));
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.tau_g1));
+        bytes.extend_from_slice(&T::g1_to_bytes(&self.nonce));
+        bytes.extend_from_slice(&T::scalar_to_bytes(&self.response));
+
+        bytes
+    }
+
+    /// Decodes a proof created by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let mut reader = Reader::new(bytes);
+        let proof = ContributionProof::read(&mut reader)?;
+        reader.finish()?;
+        Ok(proof)
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, KzgError> {
+        let alpha_g2 = reader.g2::<T>()?;
+        let tau_g1 = reader.g1::<T>()?;
+        let nonce = reader.g1::<T>()?;
+        let response = reader.scalar::<T>()?;
+        Ok(ContributionProof { alpha_g2, tau_g1, nonce, response })
+    }
+}
+
+
+impl<T: Curve> Transcript<T> {
+    /// Encodes the transcript, to be passed on to the next participant
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.initial.to_bytes();
+        bytes.extend_from_slice(&self.current.to_bytes());
+
+        bytes.extend_from_slice(&(self.proofs.len() as u64).to_be_bytes());
+        for proof in self.proofs.iter() {
+            bytes.extend_from_slice(&proof.to_bytes());
+        }
+
+        bytes
+    }
+
+    /// Decodes a transcript created by `to_bytes`
+    ///
+    /// This only decodes the keys and proofs, `Transcript::verify` checks the contributions
+    ///
+    /// # Errors
+    ///
+    /// `KzgError::InvalidEncoding` if the bytes are not a valid encoding
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        let (initial, rest) = bytes.split_at(pk_len(bytes)?);
+        let initial = Pk::from_bytes(initial)?;
+        let (current, rest) = rest.split_at(pk_len(rest)?);
+        let current = Pk::from_bytes(current)?;
+
+        let mut reader = Reader::new(rest);
+        let m = reader.u64()? as usize;
+        if m > rest.len() / CONTRIBUTION_PROOF_BYTES {
+            return Err(KzgError::InvalidEncoding);
+        }
+        let mut proofs = Vec::with_capacity(m);
+        for _ in 0..m {
+            proofs.push(ContributionProof::read(&mut reader)?);
+        }
+        reader.finish()?;
+
+        Ok(Transcript { initial, current, proofs })
+    }
+}
+
+// the length of the encoded public key at the front of the bytes
+fn pk_len(bytes: &[u8]) -> Result<usize, KzgError> {
+    let n = Reader::new(bytes).u64()?;
+    let len = usize::try_from(n).ok()
+        .and_then(|n| n.checked_mul(2 * G1_BYTES + G2_BYTES))
+        .and_then(|len| len.checked_add(8 + G1_BYTES + G2_BYTES))
+        .ok_or(KzgError::InvalidEncoding)?;
+    if len > bytes.len() {
+        return Err(KzgError::InvalidEncoding);
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use rand::random;
+    use std::collections::HashSet;
+    use crate::curve::{FastCurve, SpecCurve};
+    use crate::tape::{generate_randomness, random_scalars, Tape};
+    use crate::{commitzk, queryzk, setup};
+    use super::*;
+
+    // a public key and a commitment to {1, 2, 3} with its opening
+    fn committed_set() -> (Pk<FastCurve>, Commitment<FastCurve>, Opening<FastCurve>) {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(4, &mut rng);
+        let set: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        (pk, commitment, opening)
+    }
+
+    #[quickcheck]
+    fn test_roundtrip(is_in_set: bool) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(4, &mut rng);
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(3);
+        let kj = if is_in_set {
+            *set.iter().next().expect("set is not empty")
+        } else {
+            Curve::scalar_from_literal(&random())
+        };
+
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let proof = queryzk(&pk, &opening, kj, &mut rng).expect("opening fits the public key");
+
+        let decoded_pk = Pk::<Curve>::from_bytes(&pk.to_bytes()).expect("valid encoding");
+        let decoded_commitment = Commitment::<Curve>::from_bytes(&commitment.to_bytes()).expect("valid encoding");
+        let decoded_opening = Opening::<Curve>::from_bytes(&opening.to_bytes()).expect("valid encoding");
+        let decoded_proof = MembershipProof::<Curve>::from_bytes(&proof.to_bytes()).expect("valid encoding");
+
+        decoded_pk.to_bytes() == pk.to_bytes()
+            && decoded_commitment == commitment
+            && decoded_opening == opening
+            && decoded_proof == proof
+    }
+
+    #[test]
+    fn test_batch_membership_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let members: Vec<_> = [2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::query_members_batch(&pk, &opening, &members).expect("elements are in the set");
+        assert_eq!(BatchMembershipProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_batch_non_membership_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let non_members: Vec<_> = [4, 5].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::query_non_members_batch(&pk, &opening, &non_members, &mut rng).expect("elements are not in the set");
+        assert_eq!(BatchNonMembershipProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_set_update_roundtrip() {
+        let (pk, mut commitment, mut opening) = committed_set();
+        let four = FastCurve::scalar_from_literal(&4);
+
+        let insert = crate::update::insert(&pk, &mut opening, &mut commitment, four).expect("set fits the public key");
+        let remove = crate::update::remove(&pk, &mut opening, &mut commitment, four).expect("4 is in the set");
+        assert_eq!(SetUpdate::from_bytes(&insert.to_bytes()), Ok(insert));
+        assert_eq!(SetUpdate::from_bytes(&remove.to_bytes()), Ok(remove));
+    }
+
+    #[test]
+    fn test_multiplicity_proof_roundtrip() {
+        let (pk, _, _) = committed_set();
+        let mut rng = rand::rng();
+        let two = FastCurve::scalar_from_literal(&2);
+
+        let (_, opening) = crate::multiset::commit_multiset(&pk, &[two, two], &mut rng).expect("multiset fits the public key");
+        let proof = crate::multiset::query_multiplicity(&pk, &opening, two, 2, &mut rng).expect("2 occurs twice");
+        assert_eq!(MultiplicityProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_subset_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let subset: Vec<_> = [1, 2].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::subset::prove_subset(&pk, &opening, &subset, &mut rng).expect("subset is in the set");
+        assert_eq!(SubsetProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_disjointness_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let elements: Vec<_> = [4, 5].iter().map(FastCurve::scalar_from_literal).collect();
+
+        let proof = crate::disjoint::prove_disjoint(&pk, &opening, &elements, &mut rng).expect("no element is in the set");
+        assert_eq!(DisjointnessProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_intersection_proof_roundtrip() {
+        let (pk, _, opening) = committed_set();
+        let mut rng = rand::rng();
+        let other: HashSet<_> = [3, 4].iter().map(FastCurve::scalar_from_literal).collect();
+        let (_, other) = commitzk(&pk, &other, &mut rng).expect("set fits the public key");
+
+        let (_, proof) = crate::intersection::prove_intersection(&pk, &opening, &other, &mut rng).expect("openings fit the public key");
+        assert_eq!(IntersectionProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_ceremony_roundtrip() {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(4, &mut rng);
+
+        let (next, contribution) = crate::ceremony::contribute(&pk, &mut rng);
+        assert_eq!(ContributionProof::from_bytes(&contribution.to_bytes()), Ok(contribution));
+
+        let mut transcript = Transcript::new(next);
+        transcript.contribute(&mut rng);
+        let decoded = Transcript::<FastCurve>::from_bytes(&transcript.to_bytes()).expect("valid encoding");
+        assert_eq!(decoded.to_bytes(), transcript.to_bytes());
+    }
+
+    // the two curve implementations must produce the same bytes
+    #[test]
+    fn test_encodings_agree() {
+        let values = generate_randomness(4 * 10);
+        let set = generate_randomness(3);
+
+        let mut random = Tape::new(values.clone());
+        let fast_pk: Pk<FastCurve> = setup(3, &mut random);
+        let fast_set: HashSet<_> = set.iter().map(FastCurve::scalar_from_literal).collect();
+        let (fast_commitment, fast_opening) = commitzk(&fast_pk, &fast_set, &mut random).expect("set fits the public key");
+        let fast_proof = queryzk(&fast_pk, &fast_opening, FastCurve::scalar_from_literal(&set[0]), &mut random).expect("opening fits the public key");
+
+        let mut random = Tape::new(values);
+        let spec_pk: Pk<SpecCurve> = setup(3, &mut random);
+        let spec_set: HashSet<_> = set.iter().map(SpecCurve::scalar_from_literal).collect();
+        let (spec_commitment, spec_opening) = commitzk(&spec_pk, &spec_set, &mut random).expect("set fits the public key");
+        let spec_proof = queryzk(&spec_pk, &spec_opening, SpecCurve::scalar_from_literal(&set[0]), &mut random).expect("opening fits the public key");
+
+        assert_eq!(fast_pk.to_bytes(), spec_pk.to_bytes());
+        assert_eq!(fast_commitment.to_bytes(), spec_commitment.to_bytes());
+        assert_eq!(fast_proof.to_bytes(), spec_proof.to_bytes());
+
+        // decoding with the other implementation
+        let decoded = Pk::<SpecCurve>::from_bytes(&fast_pk.to_bytes()).expect("valid encoding");
+        assert_eq!(decoded.to_bytes(), fast_pk.to_bytes());
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let mut rng = rand::rng();
+        let pk: Pk<FastCurve> = setup(2, &mut rng);
+        let bytes = pk.to_bytes();
+
+        // trailing and missing bytes
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(Pk::<FastCurve>::from_bytes(&longer).is_err());
+        assert!(Pk::<FastCurve>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // clearing the compression flag of g^(α^2)
+        let mut uncompressed = bytes.clone();
+        uncompressed[8] &= 0x7f;
+        assert!(Pk::<FastCurve>::from_bytes(&uncompressed).is_err());
+
+        // a scalar that is larger than the group order
+        let mut proof = vec![MEMBER_TAG];
+        proof.extend_from_slice(&FastCurve::g1_to_bytes(&FastCurve::g1()));
+        proof.extend_from_slice(&[0xff; SCALAR_BYTES]);
+        assert!(MembershipProof::<FastCurve>::from_bytes(&proof).is_err());
+        assert!(MembershipProof::<SpecCurve>::from_bytes(&proof).is_err());
+
+        assert_eq!(MembershipProof::<FastCurve>::from_bytes(&[2]).err(), Some(KzgError::InvalidEncoding));
+    }
+
+    // the lengths of both polynomials are encoded, even if they differ
+    #[test]
+    fn test_mismatched_opening() {
+        let scalars: Vec<_> = (1..=5).map(|x| FastCurve::scalar_from_literal(&x)).collect();
+        let opening = Opening::<FastCurve> { phi: scalars[..3].to_vec(), phi_hat: scalars[3..].to_vec() };
+        let bytes = opening.to_bytes();
+        assert_eq!(Opening::<FastCurve>::from_bytes(&bytes), Ok(opening));
+
+        assert_eq!(Opening::<FastCurve>::from_bytes(&bytes[..bytes.len() - 1]).err(), Some(KzgError::InvalidEncoding));
+        let mut too_long = bytes.clone();
+        too_long[15] = 3;
+        assert_eq!(Opening::<FastCurve>::from_bytes(&too_long).err(), Some(KzgError::InvalidEncoding));
+    }
+}
@@ -0,0 +1,213 @@
+//! # Subset Proofs
+//!
+//! Proofs that a public list of elements is contained in the committed set.
+//!
+//! With the subset polynomial Z(x) = ∏(x - s) the prover divides φ = q·Z and
+//! phi_hat = q_hat·Z + r_hat and publishes
+//! * the hiding quotient commitment w = g^q(α) · h^q_hat(α)
+//! * R = h^r_hat(α) with a Schnorr proof of knowledge of the coefficients of r_hat
+//!
+//! The verifier checks e(C · R^-1, g2) = e(w, g2^Z(α)) using the powers of α in G2.
+//! Unlike `query_members_batch` the remainder is not revealed, only its commitment.
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use kzg::{setup, commitzk, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::subset::{prove_subset, verify_subset};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let set: HashSet<_> = [1, 2, 3].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+//!
+//! let subset: Vec<_> = [1, 3].iter().map(FastCurve::scalar_from_literal).collect();
+//! let proof = prove_subset(&pk, &opening, &subset, &mut rng).expect("subset is in the set");
+//! assert!(verify_subset(&pk, &commitment, &subset, &proof).is_ok());
+//! ```
+
+use rand::{CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{check_batch, check_opening, commit_pair, commit_poly_g2, poly, prove_hidden_polynomial, transcript_challenge, verify_hidden_polynomial};
+use crate::{Commitment, KzgError, Opening, Pk};
+
+
+/// Proof that a list of elements is contained in the committed set
+///
+/// # Fields
+///
+/// * `witness` - The quotient commitment w = g^q(α) · h^q_hat(α)
+/// * `remainder` - The remainder commitment R = h^r_hat(α)
+/// * `nonce` - The Schnorr nonce h^k(α) for a random k(x) of the same degree as r_hat(x)
+/// * `response` - The Schnorr response k(x) - c · r_hat(x), one coefficient per element
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubsetProof<T: Curve> {
+    pub witness: T::G1,
+    pub remainder: T::G1,
+    pub nonce: T::G1,
+    pub response: Vec<T::Scalar>
+}
+
+
+/// Generates a proof that every element of `subset` is in the committed set
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `subset` - The distinct elements, in any order
+/// * `rng` - Cryptographically secure random number generator
+///
+/// # Returns
+///
+/// A `SubsetProof<T>` for the subset
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::SetTooLarge` if the subset is larger than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::ElementNotInSet` if one of the elements is not in the set
+///
+pub fn prove_subset<T: Curve, R: RngCore + CryptoRng>(pk: &Pk<T>, opening: &Opening<T>, subset: &[T::Scalar], rng: &mut R) -> Result<SubsetProof<T>, KzgError> {
+    check_opening(opening)?;
+    check_batch::<T>(pk, subset)?;
+
+    let vanishing = poly::roots_polynomial::<T>(subset);
+
+    let zero = T::scalar_from_literal(&0);
+    let (quotient, remainder) = poly::divide::<T>(&opening.phi, &vanishing);
+    if quotient.is_empty() || remainder.iter().any(|x| *x != zero) {
+        return Err(KzgError::ElementNotInSet);
+    }
+
+    let (hiding_quotient, hiding_remainder) = poly::divide::<T>(&opening.phi_hat, &vanishing);
+
+    let witness = commit_pair::<T>(pk, &quotient, &hiding_quotient)?;
+
+    // the challenge is bound to the commitment the proof is checked against
+    let commitment = commit_pair::<T>(pk, &opening.phi, &opening.phi_hat)?;
+    let (remainder, nonce, response) = prove_hidden_polynomial(pk, &hiding_remainder, |remainder, nonce| {
+        transcript_challenge::<T>(b"kzg subset", subset, &[pk.h1, commitment, witness, remainder, nonce], &[])
+    }, rng)?;
+
+    Ok(SubsetProof { witness, remainder, nonce, response })
+}
+
+
+/// Verifies a proof that every element of `subset` is in the committed set
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `commitment` - The commitment returned by `commitzk`
+/// * `subset` - The elements, in any order
+/// * `proof` - The proof returned by `prove_subset`
+///
+/// # Returns
+///
+/// `Ok(())` if every element is in the set
+///
+/// # Errors
+///
+/// * `KzgError::SetTooLarge` if the subset is larger than the degree of the public key
+/// * `KzgError::DuplicateElement` if an element appears twice
+/// * `KzgError::MalformedProof` if the response does not have one coefficient per element
+/// * `KzgError::SchnorrFailed` if the proof of knowledge of r_hat is invalid
+/// * `KzgError::PairingCheckFailed` if the witness does not match the commitment
+///
+pub fn verify_subset<T: Curve>(pk: &Pk<T>, commitment: &Commitment<T>, subset: &[T::Scalar], proof: &SubsetProof<T>) -> Result<(), KzgError> {
+    check_batch::<T>(pk, subset)?;
+    if proof.response.len() != subset.len() {
+        return Err(KzgError::MalformedProof);
+    }
+
+    verify_hidden_polynomial(pk, proof.remainder, proof.nonce, &proof.response, |remainder, nonce| {
+        transcript_challenge::<T>(b"kzg subset", subset, &[pk.h1, commitment.0, proof.witness, remainder, nonce], &[])
+    })?;
+
+    let vanishing = poly::roots_polynomial::<T>(subset);
+    let vanishing_g2 = commit_poly_g2::<T>(&vanishing, &pk.g2_powers)?;
+
+    let left = T::pairing(&T::g1sub(&commitment.0, &proof.remainder), &T::g2());
+    let right = T::pairing(&proof.witness, &vanishing_g2);
+
+    if left != right {
+        return Err(KzgError::PairingCheckFailed);
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+    use crate::tape::{generate_randomness, random_scalars, setup_tape};
+    use crate::{commitzk, setup};
+    use super::*;
+
+    // this tests completeness and soundness of subset proofs
+    #[quickcheck]
+    fn test_subset(size: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let degree = 8;
+        let size = (size % 6) as usize;
+
+        let set: HashSet<_> = random_scalars::<Curve, _>(6);
+        let subset: Vec<_> = set.iter().take(size).copied().collect();
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (commitment, opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+
+        let mut rng = rand::rng();
+        let proof = prove_subset(&pk, &opening, &subset, &mut rng).expect("subset is in the set");
+        if verify_subset(&pk, &commitment, &subset, &proof).is_err() {
+            return false;
+        }
+
+        // an element outside of the set cannot be added
+        let mut larger = subset.clone();
+        larger.push(Curve::scalar_from_literal(&generate_randomness(1)[0]));
+        if prove_subset(&pk, &opening, &larger, &mut rng).err() != Some(KzgError::ElementNotInSet) {
+            return false;
+        }
+
+        // nor can the proof be reused for a different subset of the same size
+        let mut other = larger.clone();
+        other.remove(0);
+        size == 0 || verify_subset(&pk, &commitment, &other, &proof).is_err()
+    }
+
+    #[test]
+    fn test_invalid_subset_proofs() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(4, &mut rng);
+
+        let set: HashSet<_> = [1, 2, 3].iter().map(Curve::scalar_from_literal).collect();
+        let (commitment, opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let subset: Vec<_> = [1, 2].iter().map(Curve::scalar_from_literal).collect();
+        let proof = prove_subset(&pk, &opening, &subset, &mut rng).expect("subset is in the set");
+
+        let duplicates = vec![subset[0], subset[0]];
+        assert_eq!(prove_subset(&pk, &opening, &duplicates, &mut rng).err(), Some(KzgError::DuplicateElement));
+        assert_eq!(verify_subset(&pk, &commitment, &duplicates, &proof), Err(KzgError::DuplicateElement));
+
+        let mut shorter = proof.clone();
+        shorter.response.pop();
+        assert_eq!(verify_subset(&pk, &commitment, &subset, &shorter), Err(KzgError::MalformedProof));
+
+        // the prover must know the coefficients of the remainder commitment
+        let mut forged = proof.clone();
+        forged.remainder = Curve::g1add(&forged.remainder, &Curve::g1());
+        assert_eq!(verify_subset(&pk, &commitment, &subset, &forged), Err(KzgError::SchnorrFailed));
+
+        let mut forged = proof.clone();
+        forged.witness = Curve::g1add(&forged.witness, &Curve::g1());
+        assert_eq!(verify_subset(&pk, &commitment, &subset, &forged), Err(KzgError::PairingCheckFailed));
+    }
+}
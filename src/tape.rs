@@ -0,0 +1,92 @@
+//! # Deterministic Randomness Tape
+//!
+//! The protocol functions take any `RngCore + CryptoRng` as their source of randomness.
+//! For testing and for comparing the two curve implementations it is useful to replay
+//! a fixed list of values instead, which is what `Tape` does.
+//!
+//! Every value on the tape is a `u128` and values are consumed from the back of the
+//! vector, one value per 16 requested bytes.
+//! Sampling a scalar with `Curve::random_scalar` reads 64 bytes and therefore consumes four values.
+//!
+//! The module is only compiled for the tests: a tape is predictable, so it must never be the
+//! source of randomness of real secrets. It also holds the random inputs shared by the tests.
+
+use rand::{random, CryptoRng, RngCore};
+use crate::curve::Curve;
+use crate::{setup, Pk};
+
+/// Replays a fixed list of `u128` values as a random number generator
+///
+/// # Panics
+///
+/// Every read panics once the tape has run out of values
+///
+pub(crate) struct Tape {
+    values: Vec<u128>
+}
+
+impl Tape {
+    /// Creates a tape which yields `values` from last to first
+    pub(crate) fn new(values: Vec<u128>) -> Self {
+        Tape { values }
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        self.values.pop().expect("not enough randomness provided")
+    }
+}
+
+impl From<Vec<u128>> for Tape {
+    fn from(values: Vec<u128>) -> Self {
+        Tape::new(values)
+    }
+}
+
+impl RngCore for Tape {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u128() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_u128() as u64
+    }
+
+    // each started chunk of 16 bytes consumes one value
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(16) {
+            let bytes = self.next_u128().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+// the tape is only as good as the values it was created with
+// it is marked as cryptographic so the tests can pass it to the protocol functions
+impl CryptoRng for Tape {}
+
+
+/// Random non-zero values for the tests
+///
+/// Zero is skipped, as a scalar it would make a key or a blinding degenerate.
+pub(crate) fn generate_randomness(n: usize) -> Vec<u128> {
+    (0..n).map(|_| {
+        let mut r = random();
+        while r == 0 {
+            r = random();
+        }
+        r
+    }).collect()
+}
+
+/// `n` random scalars from `generate_randomness`, collected into a list or a set
+pub(crate) fn random_scalars<T: Curve, C: FromIterator<T::Scalar>>(n: usize) -> C {
+    generate_randomness(n).iter().map(T::scalar_from_literal).collect()
+}
+
+/// A public key of the given degree set up from a tape, and the tape with enough values
+/// left for two hiding commitments of that degree
+pub(crate) fn setup_tape<T: Curve>(degree: usize) -> (Pk<T>, Tape) {
+    let mut random = Tape::new(generate_randomness(4 * (2 * degree + 5)));
+    let pk = setup(degree as u128, &mut random);
+    (pk, random)
+}
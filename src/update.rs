@@ -0,0 +1,276 @@
+//! # Dynamic Sets
+//!
+//! Inserting and removing elements of a committed set without committing from scratch.
+//!
+//! The prover updates φ(x), phi_hat(x) and the commitment with `insert` and `remove`
+//! and publishes the returned `SetUpdate`. Holders of membership proofs refresh their
+//! witness and phi_hat(kj) with `update_witness`, without access to the set.
+//!
+//! * Inserting e multiplies both polynomials by (x - e), so the new witness of kj is
+//!   w' = C · w^(kj - e) with the old commitment C and phi_hat'(kj) = phi_hat(kj) · (kj - e)
+//! * Removing e divides both polynomials by (x - e), phi_hat leaves the remainder c = phi_hat(e)
+//!   which is published. The new witness of kj is w' = (w · C'^-1)^(1 / (kj - e)) with the
+//!   new commitment C' and phi_hat'(kj) = (phi_hat(kj) - c) / (kj - e)
+//!
+//! Updates are not hiding: the published element and values link the commitments
+//! before and after the update, and proofs for an inserted element reveal that
+//! phi_hat(e) = 0. Commit from scratch with `commitzk` when this matters.
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use kzg::{setup, commitzk, queryzk, verifyzk, Pk};
+//! use kzg::curve::{Curve, FastCurve};
+//! use kzg::update::{insert, update_witness};
+//!
+//! let mut rng = rand::rng();
+//! let pk: Pk<FastCurve> = setup(4, &mut rng);
+//!
+//! let set: HashSet<_> = [1, 2].iter().map(FastCurve::scalar_from_literal).collect();
+//! let (mut commitment, mut opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+//!
+//! let kj = FastCurve::scalar_from_literal(&1);
+//! let mut proof = queryzk(&pk, &opening, kj, &mut rng).expect("opening fits the public key");
+//!
+//! let update = insert(&pk, &mut opening, &mut commitment, FastCurve::scalar_from_literal(&3)).expect("set fits the public key");
+//! update_witness(&mut proof, kj, &update).expect("kj is still in the set");
+//!
+//! assert!(verifyzk(&pk, &commitment, kj, &proof).is_ok());
+//! ```
+
+use crate::curve::Curve;
+use crate::{apply, check_opening, commit_pair, multiply, poly, Commitment, KzgError, MembershipProof, Opening, Pk};
+
+
+/// Public information about a change of the committed set
+///
+/// # Variants
+///
+/// * `Insert` - `element` was inserted
+///     * `old_commitment` - The commitment before the insertion
+/// * `Remove` - `element` was removed
+///     * `new_commitment` - The commitment after the removal
+///     * `hiding_evaluation` - phi_hat(element) before the removal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetUpdate<T: Curve> {
+    Insert {
+        element: T::Scalar,
+        old_commitment: Commitment<T>
+    },
+    Remove {
+        element: T::Scalar,
+        new_commitment: Commitment<T>,
+        hiding_evaluation: T::Scalar
+    }
+}
+
+
+/// Inserts an element into a committed set
+///
+/// Updates the opening and the commitment in place with a single commitment of O(d) work.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `commitment` - The commitment matching the opening
+/// * `element` - The element to insert
+///
+/// # Returns
+///
+/// The `SetUpdate<T>` to publish to holders of membership proofs
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::ElementInSet` if the element is already in the set
+/// * `KzgError::SetTooLarge` if the set already has the maximum size of the public key
+///
+pub fn insert<T: Curve>(pk: &Pk<T>, opening: &mut Opening<T>, commitment: &mut Commitment<T>, element: T::Scalar) -> Result<SetUpdate<T>, KzgError> {
+    check_opening(opening)?;
+
+    let zero = T::scalar_from_literal(&0);
+    if apply::<T>(&opening.phi, &element) == zero {
+        return Err(KzgError::ElementInSet);
+    }
+
+    let max = pk.g_powers.len() - 1;
+    if opening.phi.len() > max {
+        return Err(KzgError::SetTooLarge { max, got: opening.phi.len() });
+    }
+
+    let factor = vec![T::scalar_from_literal(&1), zero - element];
+    let phi = multiply::<T>(&opening.phi, &factor);
+    let phi_hat = multiply::<T>(&opening.phi_hat, &factor);
+
+    let old_commitment = Commitment(commitment.0);
+    *commitment = Commitment(commit_pair::<T>(pk, &phi, &phi_hat)?);
+    *opening = Opening { phi, phi_hat };
+
+    Ok(SetUpdate::Insert { element, old_commitment })
+}
+
+
+/// Removes an element from a committed set
+///
+/// Updates the opening and the commitment in place with a single commitment of O(d) work.
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+/// * `opening` - The opening returned by `commitzk`
+/// * `commitment` - The commitment matching the opening
+/// * `element` - The element to remove
+///
+/// # Returns
+///
+/// The `SetUpdate<T>` to publish to holders of membership proofs
+///
+/// # Errors
+///
+/// * `KzgError::MalformedOpening` if φ and phi_hat are empty or differ in length
+/// * `KzgError::ElementNotInSet` if the element is not in the set
+///
+pub fn remove<T: Curve>(pk: &Pk<T>, opening: &mut Opening<T>, commitment: &mut Commitment<T>, element: T::Scalar) -> Result<SetUpdate<T>, KzgError> {
+    check_opening(opening)?;
+
+    let zero = T::scalar_from_literal(&0);
+    let factor = vec![T::scalar_from_literal(&1), zero - element];
+
+    let (phi, remainder) = poly::divide::<T>(&opening.phi, &factor);
+    if remainder[0] != zero {
+        return Err(KzgError::ElementNotInSet);
+    }
+    let (phi_hat, hiding_remainder) = poly::divide::<T>(&opening.phi_hat, &factor);
+    let hiding_evaluation = hiding_remainder[0];
+
+    *commitment = Commitment(commit_pair::<T>(pk, &phi, &phi_hat)?);
+    *opening = Opening { phi, phi_hat };
+
+    Ok(SetUpdate::Remove { element, new_commitment: Commitment(commitment.0), hiding_evaluation })
+}
+
+
+/// Refreshes a membership proof after the set was updated
+///
+/// Only needs the proof and the published update, not the set or the opening.
+/// Updates have to be applied in the order they were made.
+///
+/// # Arguments
+///
+/// * `proof` - A membership proof for kj against the commitment before the update
+/// * `kj` - The element the proof is for
+/// * `update` - The update returned by `insert` or `remove`
+///
+/// # Returns
+///
+/// `Ok(())` if the proof was updated
+///
+/// # Errors
+///
+/// * `KzgError::ElementNotInSet` if the proof is a non-membership proof or kj was removed
+/// * `KzgError::ElementInSet` if kj itself was inserted
+///
+pub fn update_witness<T: Curve>(proof: &mut MembershipProof<T>, kj: T::Scalar, update: &SetUpdate<T>) -> Result<(), KzgError> {
+    let (witness, phi_hat_kj) = match proof {
+        MembershipProof::Member { witness, phi_hat_kj } => (witness, phi_hat_kj),
+        MembershipProof::NonMember { .. } => return Err(KzgError::ElementNotInSet)
+    };
+
+    match update {
+        SetUpdate::Insert { element, old_commitment } => {
+            if *element == kj {
+                return Err(KzgError::ElementInSet);
+            }
+
+            // w' = C · w^(kj - e)
+            let difference = kj - *element;
+            *witness = T::g1add(&old_commitment.0, &T::g1mul(&difference, witness));
+            *phi_hat_kj = *phi_hat_kj * difference;
+        },
+        SetUpdate::Remove { element, new_commitment, hiding_evaluation } => {
+            if *element == kj {
+                return Err(KzgError::ElementNotInSet);
+            }
+
+            // w' = (w · C'^-1)^(1 / (kj - e))
+            let inverse = T::scalar_inv(&(kj - *element));
+            *witness = T::g1mul(&inverse, &T::g1sub(witness, &new_commitment.0));
+            *phi_hat_kj = (*phi_hat_kj - *hiding_evaluation) * inverse;
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+    use crate::tape::{random_scalars, setup_tape};
+    use crate::{commitzk, queryzk, verifyzk, setup};
+    use super::*;
+
+    // updated witnesses verify against the updated commitment
+    // and the updated opening matches a commitment to the new set
+    #[quickcheck]
+    fn test_update_witness(inserts: u8, removes: u8) -> bool {
+        use crate::curve::FastCurve as Curve;
+
+        let degree = 10;
+        let inserts = (inserts % 4) as usize;
+        let removes = (removes % 4) as usize;
+
+        let mut set: HashSet<_> = random_scalars::<Curve, _>(5);
+        let elements: Vec<_> = set.iter().copied().collect();
+        let kj = elements[0];
+
+        let (pk, mut random) = setup_tape::<Curve>(degree);
+        let (mut commitment, mut opening) = commitzk(&pk, &set, &mut random).expect("set fits the public key");
+        let mut proof = queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key");
+
+        let mut updates = Vec::new();
+        for element in random_scalars::<Curve, Vec<_>>(inserts) {
+            updates.push(insert(&pk, &mut opening, &mut commitment, element).expect("set fits the public key"));
+            set.insert(element);
+        }
+        for element in elements.iter().skip(1).take(removes) {
+            updates.push(remove(&pk, &mut opening, &mut commitment, *element).expect("element is in the set"));
+            set.remove(element);
+        }
+
+        for update in updates.iter() {
+            if update_witness(&mut proof, kj, update).is_err() {
+                return false;
+            }
+        }
+
+        opening.phi == poly::set_polynomial::<Curve>(&set)
+            && commitment == commit(&pk, &opening.phi, &opening.phi_hat).expect("opening fits the public key")
+            && verifyzk(&pk, &commitment, kj, &proof).is_ok()
+    }
+
+    #[test]
+    fn test_invalid_updates() {
+        use crate::curve::FastCurve as Curve;
+
+        let mut rng = rand::rng();
+        let pk: Pk<Curve> = setup(2, &mut rng);
+
+        let set: HashSet<_> = [1, 2].iter().map(Curve::scalar_from_literal).collect();
+        let (mut commitment, mut opening) = commitzk(&pk, &set, &mut rng).expect("set fits the public key");
+        let one = Curve::scalar_from_literal(&1);
+        let three = Curve::scalar_from_literal(&3);
+
+        assert_eq!(insert(&pk, &mut opening, &mut commitment, one).err(), Some(KzgError::ElementInSet));
+        assert_eq!(insert(&pk, &mut opening, &mut commitment, three).err(), Some(KzgError::SetTooLarge { max: 2, got: 3 }));
+        assert_eq!(remove(&pk, &mut opening, &mut commitment, three).err(), Some(KzgError::ElementNotInSet));
+
+        // the witness of a removed element cannot be updated
+        let mut proof = queryzk(&pk, &opening, one, &mut rng).expect("opening fits the public key");
+        let update = remove(&pk, &mut opening, &mut commitment, one).expect("element is in the set");
+        assert_eq!(update_witness(&mut proof, one, &update), Err(KzgError::ElementNotInSet));
+
+        // the removed element is now proven absent
+        assert!(verifyzk(&pk, &commitment, one, &queryzk(&pk, &opening, one, &mut rng).expect("opening fits the public key")).is_ok());
+    }
+}
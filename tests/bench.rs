@@ -44,9 +44,8 @@ fn print_timer(implementation: &str, length: usize, iterations: u128,  timer: Ti
 
 fn benchmark_single_iteration<T: Curve>(poly: &Vec<u128>, times: &mut Timer) {
     let degree = poly.len() + 2; 
-    let mut random = generate_randomness(poly.len() + 6);
-     
-    let kj_literal = random.pop().expect("not enough randomness provided"); 
+    let kj_literal: u128 = random(); 
+    let mut random = rand::rng();
 
     let mut timer = Instant::now();
     let pk = kzg::setup::<T>(degree as u128, &mut random);
@@ -58,17 +57,17 @@ fn benchmark_single_iteration<T: Curve>(poly: &Vec<u128>, times: &mut Timer) {
     }
     
     timer = Instant::now();
-    let (commitment, phi, phi_hat) = kzg::commitzk(&pk, &set, &mut random);
+    let (commitment, opening) = kzg::commitzk(&pk, &set, &mut random).expect("set fits the public key");
     times.1.push(timer.elapsed());
 
     let kj = T::scalar_from_literal(&kj_literal); 
 
     timer = Instant::now();
-    let (kj, witness, phi_hat_kj, pi_sj) = kzg::queryzk(&pk, &set, &phi, &phi_hat, kj, &mut random); 
+    let membership_proof = kzg::queryzk(&pk, &opening, kj, &mut random).expect("opening fits the public key");
     times.2.push(timer.elapsed());
     
     timer = Instant::now();
-    kzg::verifyzk(&pk, commitment, pi_sj, kj, witness, phi_hat_kj);
+    kzg::verifyzk(&pk, &commitment, kj, &membership_proof).expect("proof is valid");
     times.3.push(timer.elapsed());    
 }
 
@@ -115,4 +114,25 @@ fn profile_spec() {
     
     let poly = generate_randomness(30);
     benchmark_single_iteration::<SpecCurve>(&poly.clone(), &mut timer);
-}
\ No newline at end of file
+}
+
+// commitzk builds the set polynomial with a subproduct tree
+// the full protocol on these sizes takes several minutes, run with
+// cargo test --release --test bench benchmark_large_sets -- --ignored --nocapture
+#[test]
+#[ignore]
+fn benchmark_large_sets() {
+    println!();
+    for i in [1_000, 10_000, 100_000] {
+        let poly = generate_randomness(i);
+
+        let set: HashSet<_> = poly.iter().map(FastCurve::scalar_from_literal).collect();
+        let timer = Instant::now();
+        kzg::poly::set_polynomial::<FastCurve>(&set);
+        println!("set polynomial with {i} elements: \t{}ms", timer.elapsed().as_millis());
+
+        let mut timer = Timer(Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        benchmark_single_iteration::<FastCurve>(&poly, &mut timer);
+        print_timer("fast", i, 1, timer);
+    }
+}
@@ -0,0 +1,114 @@
+// Runs the deneb KZG test vectors of the consensus-spec-tests against the eip4844 module.
+//
+// KZG_TRUSTED_SETUP is the path of the trusted_setup.txt of the reference implementation and
+// KZG_TEST_VECTORS the tests/general/deneb/kzg directory of a consensus-spec-tests release.
+// The vectors are not part of the repository, so the tests are ignored by default and fail
+// when either variable is missing.
+//
+//     KZG_TRUSTED_SETUP=trusted_setup.txt KZG_TEST_VECTORS=general/deneb/kzg cargo test --release --test eip4844 -- --ignored
+
+use std::path::Path;
+use serde_yaml::Value;
+use kzg::curve::{Curve, FastCurve, SpecCurve};
+use kzg::eip4844::*;
+
+
+fn bytes(value: &Value) -> Option<Vec<u8>> {
+    let text = value.as_str()?;
+    hex::decode(text.strip_prefix("0x").unwrap_or(text)).ok()
+}
+
+fn array<const N: usize>(value: &Value) -> Option<[u8; N]> {
+    bytes(value)?.try_into().ok()
+}
+
+fn list<V>(value: &Value, parse: impl Fn(&Value) -> Option<V>) -> Option<Vec<V>> {
+    value.as_sequence()?.iter().map(parse).collect()
+}
+
+// the output of a case, None if the spec expects an error
+fn run_case<T: Curve>(setup: &TrustedSetup<T>, handler: &str, input: &Value) -> Option<Value> {
+    let hex_value = |bytes: &[u8]| Value::String(format!("0x{}", hex::encode(bytes)));
+
+    match handler {
+        "blob_to_kzg_commitment" => {
+            let commitment = blob_to_kzg_commitment(setup, &bytes(&input["blob"])?).ok()?;
+            Some(hex_value(&commitment))
+        }
+        "compute_kzg_proof" => {
+            let (proof, y) = compute_kzg_proof(setup, &bytes(&input["blob"])?, &array(&input["z"])?).ok()?;
+            Some(Value::Sequence(vec![hex_value(&proof), hex_value(&y)]))
+        }
+        "compute_blob_kzg_proof" => {
+            let proof = compute_blob_kzg_proof(setup, &bytes(&input["blob"])?, &array(&input["commitment"])?).ok()?;
+            Some(hex_value(&proof))
+        }
+        "verify_kzg_proof" => {
+            let valid = verify_kzg_proof(setup, &array(&input["commitment"])?, &array(&input["z"])?, &array(&input["y"])?, &array(&input["proof"])?).ok()?;
+            Some(Value::Bool(valid))
+        }
+        "verify_blob_kzg_proof" => {
+            let valid = verify_blob_kzg_proof(setup, &bytes(&input["blob"])?, &array(&input["commitment"])?, &array(&input["proof"])?).ok()?;
+            Some(Value::Bool(valid))
+        }
+        "verify_blob_kzg_proof_batch" => {
+            let blobs = list(&input["blobs"], bytes)?;
+            let commitments = list(&input["commitments"], array)?;
+            let proofs = list(&input["proofs"], array)?;
+            let valid = verify_blob_kzg_proof_batch(setup, &blobs, &commitments, &proofs).ok()?;
+            Some(Value::Bool(valid))
+        }
+        _ => panic!("unknown handler {handler}")
+    }
+}
+
+// walks <vectors>/<handler>/<suite>/<case>/data.yaml and returns the number of cases
+fn run_vectors<T: Curve>(setup: &TrustedSetup<T>, vectors: &Path) -> usize {
+    let mut cases = 0;
+    for handler in std::fs::read_dir(vectors).expect("test vectors are readable") {
+        let handler = handler.expect("test vectors are readable").path();
+        let name = handler.file_name().and_then(|name| name.to_str()).expect("handler names are utf-8").to_string();
+
+        for suite in std::fs::read_dir(&handler).expect("test vectors are readable") {
+            for case in std::fs::read_dir(suite.expect("test vectors are readable").path()).expect("test vectors are readable") {
+                let path = case.expect("test vectors are readable").path().join("data.yaml");
+                let data: Value = serde_yaml::from_str(&std::fs::read_to_string(&path).expect("test case is readable"))
+                    .expect("test case is valid yaml");
+
+                let expected = match &data["output"] {
+                    Value::Null => None,
+                    output => Some(output.clone())
+                };
+                assert_eq!(run_case(setup, &name, &data["input"]), expected, "{}", path.display());
+                cases += 1;
+            }
+        }
+    }
+    cases
+}
+
+fn environment() -> (String, String) {
+    let setup = std::env::var("KZG_TRUSTED_SETUP").expect("KZG_TRUSTED_SETUP is the path of trusted_setup.txt");
+    let vectors = std::env::var("KZG_TEST_VECTORS").expect("KZG_TEST_VECTORS is the path of the test vectors");
+    (std::fs::read_to_string(setup).expect("trusted setup is readable"), vectors)
+}
+
+// the test vectors are not part of the repository, run it with --ignored
+#[test]
+#[ignore]
+fn test_vectors_fast() {
+    let (text, vectors) = environment();
+
+    let setup = TrustedSetup::<FastCurve>::from_text(&text).expect("trusted setup is valid");
+    assert!(run_vectors(&setup, Path::new(&vectors)) > 0, "no test vectors found");
+}
+
+// the specification implementation is slow, run it with --ignored
+#[test]
+#[ignore]
+fn test_vectors_spec() {
+    let (text, vectors) = environment();
+
+    let setup = TrustedSetup::<SpecCurve>::from_text(&text).expect("trusted setup is valid");
+    assert!(run_vectors(&setup, Path::new(&vectors)) > 0, "no test vectors found");
+}